@@ -6,10 +6,29 @@ fn main() {
         ("valid_basic", true),
         ("valid_with_relationships", true),
         ("valid_module_custom_instructions", true),
+        ("valid_multiple_relationships", false),
+        ("valid_composite_join_keys", false),
+        ("valid_composite_primary_key_relationship", true),
         ("invalid_missing_name", false),
         ("invalid_no_tables", false),
         ("invalid_yaml_syntax", false),
         ("invalid_empty_module_instructions", false),
+        ("invalid_multiple_errors", false),
+        ("invalid_relationship_references", false),
+        ("invalid_relationship_primary_keys", false),
+        ("invalid_unknown_keys", false),
+        ("invalid_misspelled_required_key", false),
+        ("invalid_duplicate_names", false),
+        ("invalid_data_types", false),
+        ("invalid_time_dimension_types", false),
+        ("invalid_suppressions", false),
+        ("invalid_suppressions_in_block_scalars", false),
+        ("invalid_join_type", false),
+        ("invalid_spec_values", false),
+        ("invalid_expressions", false),
+        ("invalid_references", false),
+        ("invalid_aggregation", false),
+        ("invalid_metric_dependencies", false),
     ];
 
     for (name, is_valid) in fixtures {
@@ -25,7 +44,8 @@ fn main() {
 
         let output = doc.render_plain();
 
-        fs::write(&output_path, output).expect(&format!("Failed to write {}", output_path));
+        fs::write(&output_path, output)
+            .unwrap_or_else(|_| panic!("Failed to write {}", output_path));
         println!("Generated {}", output_path);
     }
 }
@@ -0,0 +1,240 @@
+use crate::diagnostic::Diagnostic;
+use crate::rules;
+use crate::{ValidationError, ValidationResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Version of the baseline file format
+pub const BASELINE_VERSION: u32 = 1;
+
+/// Findings that already existed when the baseline was written, and aren't reported again.
+///
+/// Findings are matched by file, rule and named path (see [`Diagnostic::named_path`]) rather than
+/// by line, so unrelated edits don't make them look new. Messages aren't compared, so a finding
+/// whose wording changes between versions of ssvv stays in the baseline.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub entries: Vec<BaselineEntry>,
+    /// Directory that file names in the baseline are relative to: the one containing it
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+/// The number of findings of one rule at one node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub file: String,
+    pub code: String,
+    /// Rule name, for readers of the file; not used for matching
+    pub rule: String,
+    pub path: String,
+    pub count: usize,
+}
+
+/// A baseline file that couldn't be read, parsed or written
+#[derive(Debug, Clone)]
+pub struct BaselineError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for BaselineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Baseline {}: {}", self.path.display(), self.message)
+    }
+}
+
+impl std::error::Error for BaselineError {}
+
+type Key = (String, String, String);
+
+impl Baseline {
+    /// Record every finding in `results`, for a baseline that will be saved at `path`. Files that
+    /// couldn't be read or parsed are left out, since there is nothing to record for them yet.
+    pub fn new(
+        path: &Path,
+        names: &[String],
+        results: &[Result<ValidationResult, ValidationError>],
+    ) -> Self {
+        let mut baseline = Baseline {
+            version: BASELINE_VERSION,
+            entries: Vec::new(),
+            root: root_of(path),
+        };
+
+        let mut counts: BTreeMap<Key, (&'static str, usize)> = BTreeMap::new();
+        for (name, result) in names.iter().zip(results) {
+            let diagnostics = match result {
+                Ok(result) => &result.warnings,
+                Err(error) => &error.diagnostics,
+            };
+            for diagnostic in diagnostics.iter().filter(|d| is_baselinable(d)) {
+                let entry = counts
+                    .entry(baseline.key(name, diagnostic))
+                    .or_insert((diagnostic.rule.name, 0));
+                entry.1 += 1;
+            }
+        }
+
+        baseline.entries = counts
+            .into_iter()
+            .map(|((file, code, path), (rule, count))| BaselineEntry {
+                file,
+                code,
+                rule: rule.to_string(),
+                path,
+                count,
+            })
+            .collect();
+        baseline
+    }
+
+    /// Read a baseline file
+    pub fn load(path: &Path) -> Result<Self, BaselineError> {
+        let error = |message: String| BaselineError {
+            path: path.to_path_buf(),
+            message,
+        };
+        let contents =
+            fs::read_to_string(path).map_err(|e| error(format!("failed to read: {}", e)))?;
+        let mut baseline: Baseline = serde_json::from_str(&contents)
+            .map_err(|e| error(format!("invalid baseline file: {}", e)))?;
+        if baseline.version != BASELINE_VERSION {
+            return Err(error(format!(
+                "unsupported version {} (expected {}). Write it again with --write-baseline.",
+                baseline.version, BASELINE_VERSION
+            )));
+        }
+        baseline.root = root_of(path);
+        Ok(baseline)
+    }
+
+    /// Write the baseline as pretty-printed JSON, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<(), BaselineError> {
+        let error = |e: std::io::Error| BaselineError {
+            path: path.to_path_buf(),
+            message: format!("failed to write: {}", e),
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(error)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .expect("baselines only contain JSON-compatible values");
+        fs::write(path, json + "\n").map_err(error)
+    }
+
+    /// Total number of findings recorded
+    pub fn len(&self) -> usize {
+        self.entries.iter().map(|entry| entry.count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove the diagnostics for `file_name` that are in the baseline. If a node has more findings
+    /// of a rule than were recorded, the extra ones are kept.
+    pub fn apply(&self, file_name: &str, diagnostics: &mut Vec<Diagnostic>) {
+        let mut remaining: BTreeMap<Key, usize> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let key = (entry.file.clone(), entry.code.clone(), entry.path.clone());
+                (key, entry.count)
+            })
+            .collect();
+
+        diagnostics.retain(|diagnostic| {
+            if !is_baselinable(diagnostic) {
+                return true;
+            }
+            match remaining.get_mut(&self.key(file_name, diagnostic)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            }
+        });
+    }
+
+    fn key(&self, file_name: &str, diagnostic: &Diagnostic) -> Key {
+        let path = diagnostic
+            .named_path
+            .clone()
+            .unwrap_or_else(|| diagnostic.path.to_string());
+        (
+            self.relative_file(file_name),
+            diagnostic.rule.code.to_string(),
+            path,
+        )
+    }
+
+    /// `file_name` relative to the baseline's directory, with `/` separators, so that the baseline
+    /// works wherever ssvv is run from
+    fn relative_file(&self, file_name: &str) -> String {
+        let file = Path::new(file_name);
+        let relative = match (std::path::absolute(file), std::path::absolute(&self.root)) {
+            (Ok(file), Ok(root)) => relative_to(&file, &root),
+            _ => file.to_path_buf(),
+        };
+        relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// Whether a diagnostic can be recorded. Without a parsed model there is nothing else to check, so
+/// those problems always have to be fixed.
+fn is_baselinable(diagnostic: &Diagnostic) -> bool {
+    diagnostic.rule.code != rules::INVALID_YAML.code
+        && diagnostic.rule.code != rules::INVALID_STRUCTURE.code
+        && diagnostic.rule.code != rules::UNREADABLE_FILE.code
+}
+
+fn root_of(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// `path` relative to `base`, going up with `..` where needed. Both must be absolute.
+fn relative_to(path: &Path, base: &Path) -> PathBuf {
+    let path = normalize(path);
+    let base = normalize(base);
+
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        // Different roots (e.g. drives on Windows)
+        return path.iter().collect();
+    }
+
+    let mut relative: PathBuf = base[common..]
+        .iter()
+        .map(|_| Component::ParentDir)
+        .collect();
+    relative.extend(&path[common..]);
+    relative
+}
+
+/// The components of `path` with `.` and `..` resolved
+fn normalize(path: &Path) -> Vec<Component<'_>> {
+    let mut components = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                components.pop();
+            }
+            component => components.push(component),
+        }
+    }
+    components
+}
@@ -0,0 +1,80 @@
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::{ValidationError, ValidationResult};
+
+/// A diagnostic, identified independently of its line and column, so that edits elsewhere in the
+/// file don't make an unchanged problem look new
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticKey {
+    pub file: String,
+    pub code: &'static str,
+    pub severity: Severity,
+    /// Path of the node within the document, with items identified by name where they have one,
+    /// e.g. `tables[runs].dimensions[game_id].name`, so that inserting or reordering items doesn't
+    /// change it
+    pub path: String,
+    pub message: String,
+}
+
+impl DiagnosticKey {
+    fn new(file: &str, diagnostic: &Diagnostic) -> Self {
+        DiagnosticKey {
+            file: file.to_string(),
+            code: diagnostic.rule.code,
+            severity: diagnostic.severity,
+            path: diagnostic
+                .named_path
+                .clone()
+                .unwrap_or_else(|| diagnostic.path.to_string()),
+            message: diagnostic.message.clone(),
+        }
+    }
+
+    /// The keys of every diagnostic reported for a set of files, in the order they were reported
+    pub fn collect(
+        names: &[String],
+        results: &[Result<ValidationResult, ValidationError>],
+    ) -> Vec<DiagnosticKey> {
+        names
+            .iter()
+            .zip(results)
+            .flat_map(|(name, result)| {
+                let diagnostics = match result {
+                    Ok(result) => &result.warnings,
+                    Err(error) => &error.diagnostics,
+                };
+                diagnostics.iter().map(move |d| DiagnosticKey::new(name, d))
+            })
+            .collect()
+    }
+}
+
+/// The diagnostics that appeared and were resolved between two validation runs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiagnosticChanges {
+    pub appeared: Vec<DiagnosticKey>,
+    pub resolved: Vec<DiagnosticKey>,
+}
+
+impl DiagnosticChanges {
+    /// Compare two runs. A diagnostic reported more than once counts once per occurrence.
+    pub fn between(previous: &[DiagnosticKey], current: &[DiagnosticKey]) -> Self {
+        let mut resolved: Vec<Option<&DiagnosticKey>> = previous.iter().map(Some).collect();
+        let mut appeared = Vec::new();
+
+        for key in current {
+            match resolved.iter_mut().find(|old| *old == &Some(key)) {
+                Some(old) => *old = None,
+                None => appeared.push(key.clone()),
+            }
+        }
+
+        DiagnosticChanges {
+            appeared,
+            resolved: resolved.into_iter().flatten().cloned().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.appeared.is_empty() && self.resolved.is_empty()
+    }
+}
@@ -0,0 +1,277 @@
+use crate::outcome::WarningPolicy;
+use crate::rules::{self, Rule, RULES};
+use crate::suggest;
+use crate::ValidationOptions;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the project configuration file
+pub const CONFIG_FILE_NAME: &str = "ssvv.toml";
+
+/// Rules that can't be turned off or downgraded, because without them there is no model to check
+const FIXED_RULES: &[&Rule] = &[
+    &rules::INVALID_YAML,
+    &rules::INVALID_STRUCTURE,
+    &rules::UNREADABLE_FILE,
+];
+
+/// How a rule is reported, as configured in `ssvv.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    /// Don't report the rule at all
+    Off,
+    Warning,
+    Error,
+}
+
+/// Coverage percentages at which a figure is shown as good (green) or fair (yellow). Anything
+/// lower is shown in red.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Threshold {
+    pub good: f64,
+    pub fair: f64,
+}
+
+/// Thresholds for the data quality figures in the validation summary
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoverageThresholds {
+    /// Percentage of columns with a description
+    pub described: Threshold,
+    /// Percentage of columns with synonyms
+    pub aliased: Threshold,
+}
+
+impl Default for CoverageThresholds {
+    fn default() -> Self {
+        CoverageThresholds {
+            described: Threshold {
+                good: 80.0,
+                fair: 50.0,
+            },
+            aliased: Threshold {
+                good: 60.0,
+                fair: 30.0,
+            },
+        }
+    }
+}
+
+/// Settings loaded from an `ssvv.toml`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    /// The configuration file, if the settings came from one
+    pub path: Option<PathBuf>,
+    /// Default output format, as written in the file (e.g. `json`)
+    pub format: Option<String>,
+    /// Files, directories and glob patterns to validate when none are given on the command line,
+    /// relative to the configuration file
+    pub include: Vec<String>,
+    /// Glob patterns for files and directories to skip, relative to the configuration file
+    pub exclude: Vec<glob::Pattern>,
+    pub allow_unknown_keys: bool,
+    pub warnings: WarningPolicy,
+    /// Rules whose level differs from the default, by rule code
+    pub rule_levels: HashMap<&'static str, RuleLevel>,
+    pub coverage: CoverageThresholds,
+}
+
+/// A configuration file that couldn't be read or is invalid
+#[derive(Debug, Clone)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid configuration in {}: {}",
+            self.path.display(),
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct RawConfig {
+    format: Option<String>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    allow_unknown_keys: bool,
+    deny_warnings: bool,
+    max_warnings: Option<usize>,
+    rules: BTreeMap<String, RuleLevel>,
+    coverage: RawCoverage,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawCoverage {
+    described: RawThreshold,
+    aliased: RawThreshold,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawThreshold {
+    good: Option<f64>,
+    fair: Option<f64>,
+}
+
+impl Config {
+    /// Find the configuration file that applies to `dir`: the nearest `ssvv.toml` in it or one of
+    /// its parents
+    pub fn find(dir: &Path) -> Option<PathBuf> {
+        let dir = std::path::absolute(dir).ok()?;
+        dir.ancestors()
+            .map(|ancestor| ancestor.join(CONFIG_FILE_NAME))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Load and check a configuration file
+    pub fn load(path: &Path) -> Result<Config, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(|e| ConfigError {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        Config::parse(&contents, path)
+    }
+
+    /// Parse the contents of a configuration file found at `path`
+    pub fn parse(contents: &str, path: &Path) -> Result<Config, ConfigError> {
+        let error = |message: String| ConfigError {
+            path: path.to_path_buf(),
+            message,
+        };
+        let raw: RawConfig = toml::from_str(contents).map_err(|e| error(e.to_string()))?;
+
+        let mut rule_levels = HashMap::new();
+        for (name, level) in raw.rules {
+            let Some(rule) = rules::find_rule(&name) else {
+                let mut message = format!("unknown rule '{}' in [rules].", name);
+                let names = RULES.iter().map(|rule| rule.name);
+                if let Some(candidate) = suggest::closest_match(&name, names) {
+                    message.push_str(&format!(" Did you mean '{}'?", candidate));
+                }
+                message.push_str(" Run `ssvv explain` to list every rule.");
+                return Err(error(message));
+            };
+            if FIXED_RULES.iter().any(|fixed| fixed.code == rule.code) && level != RuleLevel::Error
+            {
+                return Err(error(format!(
+                    "rule {} ({}) can't be configured, because nothing else can be checked without it",
+                    rule.code, rule.name
+                )));
+            }
+            rule_levels.insert(rule.code, level);
+        }
+
+        let defaults = CoverageThresholds::default();
+        let coverage = CoverageThresholds {
+            described: raw
+                .coverage
+                .described
+                .resolve(defaults.described)
+                .map_err(|message| error(format!("coverage.described: {}", message)))?,
+            aliased: raw
+                .coverage
+                .aliased
+                .resolve(defaults.aliased)
+                .map_err(|message| error(format!("coverage.aliased: {}", message)))?,
+        };
+
+        let exclude = raw
+            .exclude
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern)
+                    .map_err(|e| error(format!("invalid exclude pattern '{}': {}", pattern, e.msg)))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Config {
+            path: Some(path.to_path_buf()),
+            format: raw.format,
+            include: raw.include,
+            exclude,
+            allow_unknown_keys: raw.allow_unknown_keys,
+            warnings: WarningPolicy {
+                deny_warnings: raw.deny_warnings,
+                max_warnings: raw.max_warnings,
+            },
+            rule_levels,
+            coverage,
+        })
+    }
+
+    /// The directory that `include` and `exclude` are relative to
+    pub fn root(&self) -> PathBuf {
+        self.path
+            .as_deref()
+            .and_then(Path::parent)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// The options for validating the files this configuration applies to
+    pub fn validation_options(&self) -> ValidationOptions {
+        ValidationOptions {
+            allow_unknown_keys: self.allow_unknown_keys,
+            rule_levels: self.rule_levels.clone(),
+            baseline: None,
+        }
+    }
+
+    /// Whether `file` matches one of the `exclude` patterns, or is inside a directory that does
+    pub fn is_excluded(&self, file: &Path) -> bool {
+        if self.exclude.is_empty() {
+            return false;
+        }
+        let (Ok(file), Ok(root)) = (std::path::absolute(file), std::path::absolute(self.root()))
+        else {
+            return false;
+        };
+        let Ok(relative) = file.strip_prefix(&root) else {
+            return false;
+        };
+
+        relative
+            .ancestors()
+            .filter(|path| !path.as_os_str().is_empty())
+            .any(|path| {
+                self.exclude
+                    .iter()
+                    .any(|pattern| pattern.matches_path(path))
+            })
+    }
+}
+
+impl RawThreshold {
+    fn resolve(&self, default: Threshold) -> Result<Threshold, String> {
+        let threshold = Threshold {
+            good: self.good.unwrap_or(default.good),
+            fair: self.fair.unwrap_or(default.fair),
+        };
+        for value in [threshold.good, threshold.fair] {
+            if !(0.0..=100.0).contains(&value) {
+                return Err(format!("{} is not a percentage between 0 and 100", value));
+            }
+        }
+        if threshold.fair > threshold.good {
+            return Err(format!(
+                "fair ({}) can't be higher than good ({})",
+                threshold.fair, threshold.good
+            ));
+        }
+        Ok(threshold)
+    }
+}
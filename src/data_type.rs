@@ -0,0 +1,418 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest VARCHAR Snowflake allows, in characters
+pub const MAX_VARCHAR_LENGTH: u32 = 134_217_728;
+/// Longest BINARY Snowflake allows, in bytes
+pub const MAX_BINARY_LENGTH: u32 = 67_108_864;
+/// Length of a VARCHAR declared without one
+pub const DEFAULT_VARCHAR_LENGTH: u32 = 16_777_216;
+/// Length of a BINARY declared without one
+pub const DEFAULT_BINARY_LENGTH: u32 = 8_388_608;
+/// Largest precision of a NUMBER
+pub const MAX_NUMBER_PRECISION: u8 = 38;
+/// Largest fractional-seconds precision of a TIME or TIMESTAMP
+pub const MAX_TIME_PRECISION: u8 = 9;
+/// Largest dimension of a VECTOR
+pub const MAX_VECTOR_DIMENSION: u16 = 4096;
+
+/// Every type name (including aliases) that [`SnowflakeType`] understands
+pub const TYPE_NAMES: &[&str] = &[
+    "NUMBER",
+    "DECIMAL",
+    "DEC",
+    "NUMERIC",
+    "INT",
+    "INTEGER",
+    "BIGINT",
+    "SMALLINT",
+    "TINYINT",
+    "BYTEINT",
+    "FLOAT",
+    "FLOAT4",
+    "FLOAT8",
+    "DOUBLE",
+    "DOUBLE PRECISION",
+    "REAL",
+    "VARCHAR",
+    "CHAR",
+    "CHARACTER",
+    "NCHAR",
+    "STRING",
+    "TEXT",
+    "NVARCHAR",
+    "NVARCHAR2",
+    "CHAR VARYING",
+    "NCHAR VARYING",
+    "BINARY",
+    "VARBINARY",
+    "BOOLEAN",
+    "DATE",
+    "DATETIME",
+    "TIME",
+    "TIMESTAMP",
+    "TIMESTAMP_LTZ",
+    "TIMESTAMPLTZ",
+    "TIMESTAMP WITH LOCAL TIME ZONE",
+    "TIMESTAMP_NTZ",
+    "TIMESTAMPNTZ",
+    "TIMESTAMP WITHOUT TIME ZONE",
+    "TIMESTAMP_TZ",
+    "TIMESTAMPTZ",
+    "TIMESTAMP WITH TIME ZONE",
+    "VARIANT",
+    "OBJECT",
+    "ARRAY",
+    "GEOGRAPHY",
+    "GEOMETRY",
+    "VECTOR",
+];
+
+/// How a TIMESTAMP stores its time zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampKind {
+    /// TIMESTAMP_LTZ: stored in UTC, shown in the session time zone
+    Ltz,
+    /// TIMESTAMP_NTZ: wallclock time with no time zone
+    Ntz,
+    /// TIMESTAMP_TZ: stored with its own time zone offset
+    Tz,
+}
+
+/// The element type of a VECTOR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorElement {
+    Int,
+    Float,
+}
+
+/// A Snowflake data type, with aliases resolved and default parameters filled in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnowflakeType {
+    /// NUMBER and its aliases (DECIMAL, INT, BIGINT, ...)
+    Number {
+        precision: u8,
+        scale: u8,
+    },
+    /// FLOAT and its aliases (DOUBLE, REAL, ...)
+    Float,
+    /// VARCHAR and its aliases (STRING, TEXT, CHAR, ...)
+    Varchar {
+        length: u32,
+    },
+    /// BINARY and VARBINARY
+    Binary {
+        length: u32,
+    },
+    Boolean,
+    Date,
+    Time {
+        precision: u8,
+    },
+    /// The TIMESTAMP variants. Plain TIMESTAMP and DATETIME map to TIMESTAMP_NTZ, which is
+    /// Snowflake's default `TIMESTAMP_TYPE_MAPPING`.
+    Timestamp {
+        kind: TimestampKind,
+        precision: u8,
+    },
+    Variant,
+    Object,
+    Array,
+    Geography,
+    Geometry,
+    Vector {
+        element: VectorElement,
+        dimension: u16,
+    },
+}
+
+/// Why a `data_type` string isn't a valid Snowflake type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataTypeError {
+    /// The type name isn't one Snowflake knows
+    UnknownType(String),
+    /// The type name is known, but its parameters are missing, malformed or out of range
+    InvalidParameters { type_name: String, message: String },
+}
+
+impl fmt::Display for DataTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataTypeError::UnknownType(name) => {
+                write!(f, "'{}' is not a Snowflake data type", name)
+            }
+            DataTypeError::InvalidParameters { type_name, message } => {
+                write!(f, "Invalid parameters for {}: {}", type_name, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DataTypeError {}
+
+impl SnowflakeType {
+    /// Parse a type as written in a semantic model, e.g. `NUMBER(10,2)` or `timestamp_ntz(9)`
+    pub fn parse(input: &str) -> Result<Self, DataTypeError> {
+        let input = input.trim();
+        let (name, params) = match input.find('(') {
+            Some(open) => {
+                let Some(inner) = input[open + 1..].trim_end().strip_suffix(')') else {
+                    return Err(DataTypeError::InvalidParameters {
+                        type_name: normalize_name(&input[..open]),
+                        message: "missing closing ')'".to_string(),
+                    });
+                };
+                let params: Vec<&str> = inner.split(',').map(str::trim).collect();
+                (normalize_name(&input[..open]), Some(params))
+            }
+            None => (normalize_name(input), None),
+        };
+        let params = Params {
+            type_name: &name,
+            values: params.as_deref().unwrap_or(&[]),
+            present: params.is_some(),
+        };
+
+        let parsed = match name.as_str() {
+            "NUMBER" | "DECIMAL" | "DEC" | "NUMERIC" => {
+                params.count(0, 2)?;
+                let precision = params.integer(0, 1, MAX_NUMBER_PRECISION.into())?;
+                let precision = precision.unwrap_or(MAX_NUMBER_PRECISION.into()) as u8;
+                let scale = params.integer(1, 0, precision.into())?.unwrap_or(0) as u8;
+                SnowflakeType::Number { precision, scale }
+            }
+            "INT" | "INTEGER" | "BIGINT" | "SMALLINT" | "TINYINT" | "BYTEINT" => {
+                params.count(0, 0)?;
+                SnowflakeType::Number {
+                    precision: MAX_NUMBER_PRECISION,
+                    scale: 0,
+                }
+            }
+            "FLOAT" | "FLOAT4" | "FLOAT8" | "DOUBLE" | "DOUBLE PRECISION" | "REAL" => {
+                params.count(0, 0)?;
+                SnowflakeType::Float
+            }
+            "VARCHAR" | "STRING" | "TEXT" | "NVARCHAR" | "NVARCHAR2" | "CHAR VARYING"
+            | "NCHAR VARYING" => {
+                params.count(0, 1)?;
+                let length = params.integer(0, 1, MAX_VARCHAR_LENGTH)?;
+                SnowflakeType::Varchar {
+                    length: length.unwrap_or(DEFAULT_VARCHAR_LENGTH),
+                }
+            }
+            "CHAR" | "CHARACTER" | "NCHAR" => {
+                params.count(0, 1)?;
+                let length = params.integer(0, 1, MAX_VARCHAR_LENGTH)?;
+                SnowflakeType::Varchar {
+                    length: length.unwrap_or(1),
+                }
+            }
+            "BINARY" | "VARBINARY" => {
+                params.count(0, 1)?;
+                let length = params.integer(0, 1, MAX_BINARY_LENGTH)?;
+                SnowflakeType::Binary {
+                    length: length.unwrap_or(DEFAULT_BINARY_LENGTH),
+                }
+            }
+            "BOOLEAN" => {
+                params.count(0, 0)?;
+                SnowflakeType::Boolean
+            }
+            "DATE" => {
+                params.count(0, 0)?;
+                SnowflakeType::Date
+            }
+            "TIME" => SnowflakeType::Time {
+                precision: params.time_precision()?,
+            },
+            "TIMESTAMP"
+            | "DATETIME"
+            | "TIMESTAMP_NTZ"
+            | "TIMESTAMPNTZ"
+            | "TIMESTAMP WITHOUT TIME ZONE" => SnowflakeType::Timestamp {
+                kind: TimestampKind::Ntz,
+                precision: params.time_precision()?,
+            },
+            "TIMESTAMP_LTZ" | "TIMESTAMPLTZ" | "TIMESTAMP WITH LOCAL TIME ZONE" => {
+                SnowflakeType::Timestamp {
+                    kind: TimestampKind::Ltz,
+                    precision: params.time_precision()?,
+                }
+            }
+            "TIMESTAMP_TZ" | "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" => {
+                SnowflakeType::Timestamp {
+                    kind: TimestampKind::Tz,
+                    precision: params.time_precision()?,
+                }
+            }
+            "VARIANT" | "OBJECT" | "ARRAY" | "GEOGRAPHY" | "GEOMETRY" => {
+                // Structured OBJECT(...) and ARRAY(...) types are accepted without further checks
+                if name == "VARIANT" || name.starts_with("GEO") {
+                    params.count(0, 0)?;
+                }
+                match name.as_str() {
+                    "VARIANT" => SnowflakeType::Variant,
+                    "OBJECT" => SnowflakeType::Object,
+                    "ARRAY" => SnowflakeType::Array,
+                    "GEOGRAPHY" => SnowflakeType::Geography,
+                    _ => SnowflakeType::Geometry,
+                }
+            }
+            "VECTOR" => {
+                params.count(2, 2)?;
+                let element = match normalize_name(params.values[0]).as_str() {
+                    "INT" => VectorElement::Int,
+                    "FLOAT" => VectorElement::Float,
+                    other => {
+                        return Err(params.error(format!(
+                            "element type must be INT or FLOAT, not '{}'",
+                            other
+                        )))
+                    }
+                };
+                let dimension = params.integer(1, 1, MAX_VECTOR_DIMENSION.into())?;
+                SnowflakeType::Vector {
+                    element,
+                    dimension: dimension.unwrap_or(1) as u16,
+                }
+            }
+            _ => return Err(DataTypeError::UnknownType(input.to_string())),
+        };
+
+        Ok(parsed)
+    }
+
+    /// Whether values of this type are dates or times
+    pub fn is_temporal(&self) -> bool {
+        matches!(
+            self,
+            SnowflakeType::Date | SnowflakeType::Time { .. } | SnowflakeType::Timestamp { .. }
+        )
+    }
+
+    /// Whether values of this type are numbers
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, SnowflakeType::Number { .. } | SnowflakeType::Float)
+    }
+}
+
+impl FromStr for SnowflakeType {
+    type Err = DataTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SnowflakeType::parse(s)
+    }
+}
+
+impl fmt::Display for SnowflakeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnowflakeType::Number { precision, scale } => {
+                write!(f, "NUMBER({},{})", precision, scale)
+            }
+            SnowflakeType::Float => write!(f, "FLOAT"),
+            SnowflakeType::Varchar { length } => write!(f, "VARCHAR({})", length),
+            SnowflakeType::Binary { length } => write!(f, "BINARY({})", length),
+            SnowflakeType::Boolean => write!(f, "BOOLEAN"),
+            SnowflakeType::Date => write!(f, "DATE"),
+            SnowflakeType::Time { precision } => write!(f, "TIME({})", precision),
+            SnowflakeType::Timestamp { kind, precision } => {
+                let suffix = match kind {
+                    TimestampKind::Ltz => "LTZ",
+                    TimestampKind::Ntz => "NTZ",
+                    TimestampKind::Tz => "TZ",
+                };
+                write!(f, "TIMESTAMP_{}({})", suffix, precision)
+            }
+            SnowflakeType::Variant => write!(f, "VARIANT"),
+            SnowflakeType::Object => write!(f, "OBJECT"),
+            SnowflakeType::Array => write!(f, "ARRAY"),
+            SnowflakeType::Geography => write!(f, "GEOGRAPHY"),
+            SnowflakeType::Geometry => write!(f, "GEOMETRY"),
+            SnowflakeType::Vector { element, dimension } => {
+                let element = match element {
+                    VectorElement::Int => "INT",
+                    VectorElement::Float => "FLOAT",
+                };
+                write!(f, "VECTOR({}, {})", element, dimension)
+            }
+        }
+    }
+}
+
+/// Uppercase a type name and collapse runs of whitespace, so `double   precision` matches
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase()
+}
+
+/// The parenthesised parameters of a type, if any
+struct Params<'a> {
+    type_name: &'a str,
+    values: &'a [&'a str],
+    /// Whether parentheses were written at all, so `VARCHAR()` is rejected
+    present: bool,
+}
+
+impl Params<'_> {
+    fn error(&self, message: impl Into<String>) -> DataTypeError {
+        DataTypeError::InvalidParameters {
+            type_name: self.type_name.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Check that between `min` and `max` parameters were given
+    fn count(&self, min: usize, max: usize) -> Result<(), DataTypeError> {
+        let count = self.values.len();
+        if max == 0 && self.present {
+            return Err(self.error("takes no parameters"));
+        }
+        if self.present && self.values.iter().any(|v| v.is_empty()) {
+            return Err(self.error("empty parameter"));
+        }
+        if count < min || count > max {
+            let expected = if min == max {
+                format!("exactly {}", min)
+            } else {
+                format!("at most {}", max)
+            };
+            return Err(self.error(format!("expected {} parameters, found {}", expected, count)));
+        }
+        Ok(())
+    }
+
+    /// The integer parameter at `index`, checked to be within `min..=max`
+    fn integer(&self, index: usize, min: u32, max: u32) -> Result<Option<u32>, DataTypeError> {
+        let Some(value) = self.values.get(index) else {
+            return Ok(None);
+        };
+        let name = match (self.type_name, index) {
+            ("VECTOR", _) => "dimension",
+            (_, 1) => "scale",
+            (name, _) if name.starts_with("TIME") || name == "DATETIME" => "precision",
+            (name, _) if name.contains("CHAR") || name.contains("BINARY") => "length",
+            ("STRING" | "TEXT", _) => "length",
+            _ => "precision",
+        };
+        let parsed: u32 = value
+            .parse()
+            .map_err(|_| self.error(format!("{} '{}' is not a whole number", name, value)))?;
+        if parsed < min || parsed > max {
+            return Err(self.error(format!(
+                "{} {} is out of range (must be between {} and {})",
+                name, parsed, min, max
+            )));
+        }
+        Ok(Some(parsed))
+    }
+
+    /// The optional fractional-seconds precision of a TIME or TIMESTAMP
+    fn time_precision(&self) -> Result<u8, DataTypeError> {
+        self.count(0, 1)?;
+        let precision = self.integer(0, 0, MAX_TIME_PRECISION.into())?;
+        Ok(precision.unwrap_or(MAX_TIME_PRECISION.into()) as u8)
+    }
+}
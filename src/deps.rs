@@ -0,0 +1,264 @@
+use crate::diagnostic::NodePath;
+use crate::sql_expr::{self, Reference};
+use crate::SemanticModel;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+/// What an `expr` defines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExprKind {
+    Dimension,
+    TimeDimension,
+    Fact,
+    Metric,
+    Filter,
+}
+
+impl ExprKind {
+    /// Lowercase label used in messages (e.g. "time dimension")
+    pub fn label(self) -> &'static str {
+        match self {
+            ExprKind::Dimension => "dimension",
+            ExprKind::TimeDimension => "time dimension",
+            ExprKind::Fact => "fact",
+            ExprKind::Metric => "metric",
+            ExprKind::Filter => "filter",
+        }
+    }
+}
+
+/// A dimension, time dimension, fact or metric in a [`DependencyGraph`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphItem {
+    pub kind: ExprKind,
+    pub name: String,
+    /// The table the item belongs to, or `None` for model-level metrics
+    pub table: Option<String>,
+    pub expr: String,
+    /// The item's node in the document, e.g. `tables[0].metrics[1]`
+    pub path: NodePath,
+}
+
+impl GraphItem {
+    /// The name qualified with its table, e.g. `ORDERS.total_revenue`, as an expression would
+    /// refer to it from elsewhere
+    pub fn qualified_name(&self) -> String {
+        match &self.table {
+            Some(table) => format!("{}.{}", table, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// The columns and metrics each metric of a model is computed from.
+///
+/// Dimensions, time dimensions and facts are defined over physical columns, so they have no
+/// dependencies in the graph. Names that don't resolve, and expressions that don't parse, are left
+/// out; validation reports them.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    items: Vec<GraphItem>,
+    /// For each item, the items its expr refers to, in order and without duplicates
+    dependencies: Vec<Vec<usize>>,
+}
+
+impl DependencyGraph {
+    /// Build the graph of every table's columns and metrics, followed by the model-level metrics
+    pub fn new(model: &SemanticModel) -> Self {
+        let mut graph = DependencyGraph::default();
+
+        for (i, table) in model.tables.iter().enumerate() {
+            let table_path = NodePath::root().key("tables").index(i);
+            let mut add = |kind, key: &str, j: usize, name: &str, expr: &str| {
+                graph.items.push(GraphItem {
+                    kind,
+                    name: name.to_string(),
+                    table: Some(table.name.clone()),
+                    expr: expr.to_string(),
+                    path: table_path.key(key).index(j),
+                })
+            };
+            for (j, d) in table.dimensions.iter().enumerate() {
+                add(ExprKind::Dimension, "dimensions", j, &d.name, &d.expr);
+            }
+            for (j, d) in table.time_dimensions.iter().enumerate() {
+                add(
+                    ExprKind::TimeDimension,
+                    "time_dimensions",
+                    j,
+                    &d.name,
+                    &d.expr,
+                );
+            }
+            for (j, f) in table.facts.iter().enumerate() {
+                add(ExprKind::Fact, "facts", j, &f.name, &f.expr);
+            }
+            for (j, m) in table.metrics.iter().enumerate() {
+                add(ExprKind::Metric, "metrics", j, &m.name, &m.expr);
+            }
+        }
+        for (i, metric) in model.metrics.iter().enumerate() {
+            graph.items.push(GraphItem {
+                kind: ExprKind::Metric,
+                name: metric.name.clone(),
+                table: None,
+                expr: metric.expr.clone(),
+                path: NodePath::root().key("metrics").index(i),
+            });
+        }
+
+        graph.dependencies = graph
+            .items
+            .iter()
+            .map(|item| {
+                let Ok(expr) = sql_expr::parse(&item.expr) else {
+                    return Vec::new();
+                };
+                if item.kind != ExprKind::Metric {
+                    return Vec::new();
+                }
+                let mut dependencies = Vec::new();
+                for reference in sql_expr::analyze(&expr).references {
+                    let Some(dependency) = graph.resolve(item.table.as_deref(), &reference) else {
+                        continue;
+                    };
+                    if !dependencies.contains(&dependency) {
+                        dependencies.push(dependency);
+                    }
+                }
+                dependencies
+            })
+            .collect();
+
+        graph
+    }
+
+    pub fn items(&self) -> &[GraphItem] {
+        &self.items
+    }
+
+    /// The items that `item`'s expr refers to
+    pub fn dependencies(&self, item: usize) -> &[usize] {
+        &self.dependencies[item]
+    }
+
+    /// The item a name refers to, in an expression of `table` (or of a model-level metric, if
+    /// `None`). Within a table, unqualified names are its own columns and metrics; in model-level
+    /// metrics, they are metrics of any table or of the model. A column takes precedence over a
+    /// metric with the same name.
+    pub(crate) fn resolve(&self, table: Option<&str>, reference: &Reference) -> Option<usize> {
+        let part = match reference.parts.len() {
+            1 => 0,
+            2 => 1,
+            // Longer names, like paths into semi-structured columns, aren't items
+            _ => return None,
+        };
+        let in_scope = |item: &GraphItem| match (part, table) {
+            (0, Some(table)) => item.table.as_deref() == Some(table),
+            (0, None) => item.kind == ExprKind::Metric,
+            _ => item
+                .table
+                .as_deref()
+                .is_some_and(|table| reference.part_is(0, table)),
+        };
+
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| in_scope(item) && reference.part_is(part, &item.name))
+            .min_by_key(|(_, item)| item.kind == ExprKind::Metric)
+            .map(|(index, _)| index)
+    }
+
+    /// The metrics called `name`, ignoring case. `TABLE.NAME` only matches a metric of that table;
+    /// a bare name matches model-level metrics and the metrics of every table.
+    pub fn find_metrics(&self, name: &str) -> Vec<usize> {
+        let (table, name) = match name.split_once('.') {
+            Some((table, name)) => (Some(table), name),
+            None => (None, name),
+        };
+        (0..self.items.len())
+            .filter(|&index| {
+                let item = &self.items[index];
+                item.kind == ExprKind::Metric
+                    && item.name.eq_ignore_ascii_case(name)
+                    && table.is_none_or(|table| {
+                        item.table
+                            .as_deref()
+                            .is_some_and(|own| own.eq_ignore_ascii_case(table))
+                    })
+            })
+            .collect()
+    }
+
+    /// Metrics that depend on themselves, each as a path from a metric back to itself, e.g.
+    /// `[a, b, a]`. A cycle is reported once, from whichever of its metrics comes first.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut in_cycle = vec![false; self.items.len()];
+        let mut cycles = Vec::new();
+
+        for start in 0..self.items.len() {
+            if in_cycle[start] {
+                continue;
+            }
+            if let Some(cycle) = self.shortest_cycle(start) {
+                for &item in &cycle {
+                    in_cycle[item] = true;
+                }
+                cycles.push(cycle);
+            }
+        }
+
+        cycles
+    }
+
+    /// The shortest path of dependencies from `start` back to itself, if there is one
+    fn shortest_cycle(&self, start: usize) -> Option<Vec<usize>> {
+        let mut parent: HashMap<usize, usize> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(current) = queue.pop_front() {
+            for &next in &self.dependencies[current] {
+                if next == start {
+                    let mut chain = Vec::new();
+                    let mut item = current;
+                    while item != start {
+                        chain.push(item);
+                        item = parent[&item];
+                    }
+                    chain.reverse();
+
+                    let mut cycle = vec![start];
+                    cycle.extend(chain);
+                    cycle.push(start);
+                    return Some(cycle);
+                }
+                if let std::collections::hash_map::Entry::Vacant(entry) = parent.entry(next) {
+                    entry.insert(current);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The tables whose columns and metrics `item` uses, directly or through other metrics,
+    /// including its own
+    pub fn tables_used(&self, item: usize) -> BTreeSet<String> {
+        let mut tables = BTreeSet::new();
+        let mut seen = vec![false; self.items.len()];
+        let mut stack = vec![item];
+
+        while let Some(current) = stack.pop() {
+            if std::mem::replace(&mut seen[current], true) {
+                continue;
+            }
+            if let Some(table) = &self.items[current].table {
+                tables.insert(table.clone());
+            }
+            stack.extend(&self.dependencies[current]);
+        }
+
+        tables
+    }
+}
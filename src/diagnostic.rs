@@ -0,0 +1,207 @@
+use crate::rules::Rule;
+use crate::yaml_tree::Span;
+use std::fmt;
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    /// Lowercase label used when rendering (e.g. "error")
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// One step in a [`NodePath`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// The location of a node within the semantic model document, e.g. `tables[0].dimensions[2].name`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct NodePath(Vec<PathSegment>);
+
+impl NodePath {
+    /// The path of the document root
+    pub fn root() -> Self {
+        NodePath(Vec::new())
+    }
+
+    /// Extend the path with a mapping key
+    pub fn key(&self, key: impl Into<String>) -> Self {
+        let mut segments = self.0.clone();
+        segments.push(PathSegment::Key(key.into()));
+        NodePath(segments)
+    }
+
+    /// Extend the path with a sequence index
+    pub fn index(&self, index: usize) -> Self {
+        let mut segments = self.0.clone();
+        segments.push(PathSegment::Index(index));
+        NodePath(segments)
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    /// Whether this path is `ancestor` or somewhere inside it
+    pub fn starts_with(&self, ancestor: &NodePath) -> bool {
+        self.0.starts_with(&ancestor.0)
+    }
+}
+
+impl fmt::Display for NodePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i == 0 => write!(f, "{}", key)?,
+                PathSegment::Key(key) => write!(f, ".{}", key)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where a diagnostic points in its source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: String,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column number
+    pub column: usize,
+    /// Number of characters to underline, starting at `column`
+    pub length: usize,
+    /// The full text of the line, for rendering a snippet
+    pub source_line: String,
+}
+
+impl Location {
+    pub fn new(
+        file: impl Into<String>,
+        source: &str,
+        line: usize,
+        column: usize,
+        length: usize,
+    ) -> Self {
+        Location {
+            file: file.into(),
+            line,
+            column,
+            length,
+            source_line: source
+                .lines()
+                .nth(line.saturating_sub(1))
+                .unwrap_or("")
+                .to_string(),
+        }
+    }
+
+    /// The column just past the last underlined character. Every output that reports an end
+    /// column uses this exclusive convention, which is the one SARIF's `endColumn` defines.
+    pub fn end_column(&self) -> usize {
+        self.column + self.length
+    }
+}
+
+/// Another place in the document that helps explain a diagnostic, such as the first definition of
+/// a duplicated name
+#[derive(Debug, Clone)]
+pub struct Related {
+    pub message: String,
+    pub path: NodePath,
+    /// Source position of `path`, filled in alongside the diagnostic's own location
+    pub location: Option<Location>,
+}
+
+/// A single problem found while validating a semantic model
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// The rule that produced this diagnostic
+    pub rule: &'static Rule,
+    pub severity: Severity,
+    pub message: String,
+    pub suggestion: Option<String>,
+    /// Name of the table the diagnostic belongs to, or `None` for model-level problems
+    pub table: Option<String>,
+    /// The node in the document the diagnostic is about
+    pub path: NodePath,
+    /// Source position of `path`, filled in once the diagnostic is tied to a file
+    pub location: Option<Location>,
+    /// For problems inside a scalar value, such as a syntax error in an `expr`: the 1-based line
+    /// and column within the value to point `location` at, instead of the node as a whole
+    pub value_span: Option<Span>,
+    /// `path` with list items identified by their `name` where they have one, e.g.
+    /// `tables[runs].dimensions[run_id]`. Unlike `path`, it survives items being added, removed or
+    /// reordered. Filled in along with `location`.
+    pub named_path: Option<String>,
+    /// Other nodes involved in the problem
+    pub related: Vec<Related>,
+}
+
+impl Diagnostic {
+    /// Create a diagnostic for `rule`, using the rule's default severity
+    pub fn new(rule: &'static Rule, message: impl Into<String>) -> Self {
+        Diagnostic {
+            rule,
+            severity: rule.severity,
+            message: message.into(),
+            suggestion: None,
+            table: None,
+            path: NodePath::root(),
+            location: None,
+            value_span: None,
+            named_path: None,
+            related: Vec::new(),
+        }
+    }
+
+    /// Attach a suggestion on how to fix the problem
+    pub fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    /// Attribute the diagnostic to a table
+    pub fn in_table(mut self, table: impl Into<String>) -> Self {
+        self.table = Some(table.into());
+        self
+    }
+
+    /// Point the diagnostic at a node in the document
+    pub fn at(mut self, path: NodePath) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// Point at a position inside the value of the node, rather than the whole node
+    pub fn within_value(mut self, span: Span) -> Self {
+        self.value_span = Some(span);
+        self
+    }
+
+    /// Point at another node involved in the problem
+    pub fn with_related(mut self, path: NodePath, message: impl Into<String>) -> Self {
+        self.related.push(Related {
+            message: message.into(),
+            path,
+            location: None,
+        });
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The semantic model files selected by a set of command-line paths
+#[derive(Debug, Clone, Default)]
+pub struct FileSet {
+    /// Files to validate, in the order given, without duplicates
+    pub files: Vec<PathBuf>,
+    /// Directories and glob patterns that matched no YAML files
+    pub unmatched: Vec<String>,
+}
+
+/// Expand files, directories (searched recursively for `*.yaml` and `*.yml`) and glob patterns
+/// into the list of files to validate.
+///
+/// Plain file paths are kept even if they don't exist, so that validating them reports the problem.
+pub fn collect_model_files(inputs: &[String]) -> FileSet {
+    let mut set = FileSet::default();
+
+    for input in inputs {
+        let before = set.files.len();
+        let path = Path::new(input);
+
+        if path.is_dir() {
+            walk_directory(path, &mut set.files);
+        } else if is_glob(input) {
+            let matches = glob::glob(input).into_iter().flatten().flatten();
+            for matched in matches {
+                if matched.is_dir() {
+                    walk_directory(&matched, &mut set.files);
+                } else if is_yaml(&matched) {
+                    push_unique(&mut set.files, matched);
+                }
+            }
+        } else {
+            push_unique(&mut set.files, path.to_path_buf());
+            continue;
+        }
+
+        if set.files.len() == before {
+            set.unmatched.push(input.clone());
+        }
+    }
+
+    set
+}
+
+fn is_glob(input: &str) -> bool {
+    input.contains(['*', '?', '['])
+}
+
+fn is_yaml(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("yaml" | "yml")
+    )
+}
+
+fn push_unique(files: &mut Vec<PathBuf>, path: PathBuf) {
+    if !files.contains(&path) {
+        files.push(path);
+    }
+}
+
+/// Add every YAML file under `dir`, in sorted order, skipping hidden files and directories
+fn walk_directory(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with('.'))
+        })
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        if path.is_dir() {
+            walk_directory(&path, files);
+        } else if is_yaml(&path) {
+            push_unique(files, path);
+        }
+    }
+}
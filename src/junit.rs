@@ -0,0 +1,149 @@
+use crate::report::{DiagnosticReport, FileReport, Report};
+use crate::rules::{self, RULES};
+use std::fmt::Write;
+
+impl Report {
+    /// Render the report as JUnit XML, with one testsuite per file and one testcase per rule
+    pub fn to_junit(&self) -> String {
+        let tests = self.results.len() * RULES.len();
+        let failures: usize = self.results.iter().map(failed_rules).sum();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuites name=\"ssvv\" tests=\"{}\" failures=\"{}\">",
+            tests, failures
+        );
+        for file in &self.results {
+            write_testsuite(&mut xml, file);
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+/// Whether the file could be parsed at all. If not, most rules never ran.
+fn parsed(file: &FileReport) -> bool {
+    !file.diagnostics.iter().any(|d| {
+        d.code == rules::INVALID_YAML.code
+            || d.code == rules::INVALID_STRUCTURE.code
+            || d.code == rules::UNREADABLE_FILE.code
+    })
+}
+
+fn errors_for<'a>(
+    file: &'a FileReport,
+    code: &'a str,
+) -> impl Iterator<Item = &'a DiagnosticReport> + 'a {
+    file.diagnostics
+        .iter()
+        .filter(move |d| d.code == code && d.severity == "error")
+}
+
+fn failed_rules(file: &FileReport) -> usize {
+    RULES
+        .iter()
+        .filter(|rule| errors_for(file, rule.code).next().is_some())
+        .count()
+}
+
+fn write_testsuite(xml: &mut String, file: &FileReport) {
+    let failures = failed_rules(file);
+    let skipped = if parsed(file) {
+        0
+    } else {
+        RULES.len() - failures
+    };
+
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\">",
+        escape(&file.file),
+        RULES.len(),
+        failures,
+        skipped
+    );
+
+    for rule in RULES {
+        let _ = write!(
+            xml,
+            "    <testcase name=\"{} {}\" classname=\"{}\"",
+            rule.code,
+            rule.name,
+            escape(&file.file)
+        );
+
+        let errors: Vec<&DiagnosticReport> = errors_for(file, rule.code).collect();
+        let warnings: Vec<&DiagnosticReport> = file
+            .diagnostics
+            .iter()
+            .filter(|d| d.code == rule.code && d.severity != "error")
+            .collect();
+
+        if errors.is_empty() && warnings.is_empty() && skipped == 0 {
+            xml.push_str("/>\n");
+            continue;
+        }
+        xml.push_str(">\n");
+
+        if let Some(first) = errors.first() {
+            let _ = writeln!(
+                xml,
+                "      <failure type=\"{}\" message=\"{}\">{}</failure>",
+                rule.code,
+                escape(&first.message),
+                escape(&describe(&file.file, &errors))
+            );
+        } else if skipped > 0 {
+            xml.push_str("      <skipped message=\"The model could not be parsed\"/>\n");
+        }
+        if !warnings.is_empty() {
+            let _ = writeln!(
+                xml,
+                "      <system-out>{}</system-out>",
+                escape(&describe(&file.file, &warnings))
+            );
+        }
+
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n");
+}
+
+/// One line per diagnostic, prefixed with its location, followed by any suggestion
+fn describe(file: &str, diagnostics: &[&DiagnosticReport]) -> String {
+    let mut text = String::new();
+    for diagnostic in diagnostics {
+        match (diagnostic.line, diagnostic.column) {
+            (Some(line), Some(column)) => {
+                let _ = write!(text, "{}:{}:{}: ", file, line, column);
+            }
+            _ => {
+                let _ = write!(text, "{}: ", file);
+            }
+        }
+        let _ = writeln!(text, "{}: {}", diagnostic.severity, diagnostic.message);
+        if let Some(suggestion) = &diagnostic.suggestion {
+            for line in suggestion.lines() {
+                let _ = writeln!(text, "  {}", line);
+            }
+        }
+    }
+    text
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
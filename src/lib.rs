@@ -1,14 +1,53 @@
+mod baseline;
+mod changes;
 mod colored_doc;
-
+mod config;
+mod data_type;
+mod deps;
+mod diagnostic;
+mod files;
+mod junit;
+mod outcome;
+mod report;
+pub mod rules;
+mod sarif;
+mod schema;
+mod spec_enum;
+mod sql_expr;
+mod suggest;
+mod suppress;
+mod yaml_tree;
+
+pub use baseline::{Baseline, BaselineEntry, BaselineError, BASELINE_VERSION};
+pub use changes::{DiagnosticChanges, DiagnosticKey};
 pub use colored_doc::{
     color_spec, dimmed_spec, heading, separator, subheading, Alignment, Cell, ColoredDoc, Column,
     Table as TableRenderer,
 };
+pub use config::{Config, ConfigError, CoverageThresholds, RuleLevel, Threshold, CONFIG_FILE_NAME};
+pub use data_type::{DataTypeError, SnowflakeType, TimestampKind, VectorElement};
+pub use deps::{DependencyGraph, ExprKind, GraphItem};
+pub use diagnostic::{Diagnostic, Location, NodePath, PathSegment, Related, Severity};
+pub use files::{collect_model_files, FileSet};
+pub use outcome::{Outcome, WarningPolicy};
+pub use report::{
+    DataQuality, DiagnosticReport, FileReport, ModelSummary, RelatedReport, Report, TableSummary,
+    REPORT_VERSION,
+};
+pub use rules::{find_rule, Rule, RULES};
+pub use spec_enum::{AccessModifier, Aggregation, JoinType, RelationshipType, SpecEnum};
+pub use suggest::did_you_mean;
+pub use yaml_tree::Span;
 
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
 use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use suppress::Suppressions;
 use termcolor::Color;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -120,9 +159,9 @@ pub struct Fact {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sample_values: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub aggregation: Option<String>,
+    pub aggregation: Option<Aggregation>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub access_modifier: Option<String>,
+    pub access_modifier: Option<AccessModifier>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -138,7 +177,7 @@ pub struct Metric {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sample_values: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub access_modifier: Option<String>,
+    pub access_modifier: Option<AccessModifier>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -159,8 +198,8 @@ pub struct Relationship {
     pub left_table: String,
     pub right_table: String,
     pub relationship_columns: Vec<RelationshipColumn>,
-    pub join_type: String,
-    pub relationship_type: String,
+    pub join_type: JoinType,
+    pub relationship_type: RelationshipType,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -185,72 +224,352 @@ pub struct VerifiedQuery {
     pub name: String,
     pub question: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub sql: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub verified_query: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub verified_result: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_as_onboarding_question: Option<bool>,
 }
 
+/// The full set of diagnostics for a model that failed validation
 #[derive(Debug, Clone)]
 pub struct ValidationError {
-    pub message: String,
-    pub is_yaml_error: bool,
+    /// Every error and warning found, in the order they were detected
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationError {
+    fn single(rule: &'static Rule, message: impl Into<String>) -> Self {
+        ValidationError {
+            diagnostics: vec![Diagnostic::new(rule, message)],
+        }
+    }
+
+    /// Whether the file failed to parse as YAML at all
+    pub fn is_yaml_error(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.rule == &rules::INVALID_YAML)
+    }
+
+    /// Whether the file couldn't be read as a semantic model, so most rules never ran
+    pub fn is_structure_error(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.rule == &rules::INVALID_STRUCTURE)
+    }
+
+    /// The diagnostics with error severity
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.is_error())
+    }
+
+    /// The diagnostics with warning severity
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| !d.is_error())
+    }
 }
 
 impl fmt::Display for ValidationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.message)
+        let messages: Vec<&str> = self.errors().map(|d| d.message.as_str()).collect();
+        write!(f, "{}", messages.join("\n"))
     }
 }
 
 impl std::error::Error for ValidationError {}
 
-#[derive(Debug, Clone)]
-pub struct ValidationWarning {
-    pub message: String,
-    pub suggestion: Option<String>,
+/// Settings that change which checks are run
+#[derive(Debug, Clone, Default)]
+pub struct ValidationOptions {
+    /// Skip reporting keys that aren't part of the semantic model spec
+    pub allow_unknown_keys: bool,
+    /// Rules to report at a different level than their default, by rule code
+    pub rule_levels: HashMap<&'static str, RuleLevel>,
+    /// Existing findings not to report again
+    pub baseline: Option<Arc<Baseline>>,
+}
+
+impl ValidationOptions {
+    /// The severity to report `rule` with, or `None` if it is turned off
+    pub fn severity(&self, rule: &Rule) -> Option<Severity> {
+        match self.rule_levels.get(rule.code) {
+            None => Some(rule.severity),
+            Some(RuleLevel::Off) => None,
+            Some(RuleLevel::Warning) => Some(Severity::Warning),
+            Some(RuleLevel::Error) => Some(Severity::Error),
+        }
+    }
+
+    /// Drop diagnostics for rules that are turned off, and apply configured severities
+    fn apply_rule_levels(&self, diagnostics: &mut Vec<Diagnostic>) {
+        diagnostics.retain_mut(|diagnostic| match self.severity(diagnostic.rule) {
+            Some(severity) => {
+                diagnostic.severity = severity;
+                true
+            }
+            None => false,
+        });
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct ValidationResult {
     pub model: SemanticModel,
-    pub warnings: Vec<ValidationWarning>,
+    pub warnings: Vec<Diagnostic>,
 }
 
 /// Parse and validate a semantic model file
 pub fn validate_file(path: impl AsRef<Path>) -> Result<ValidationResult, ValidationError> {
+    validate_file_with_options(path, &ValidationOptions::default())
+}
+
+/// Parse and validate a semantic model file, with non-default settings
+pub fn validate_file_with_options(
+    path: impl AsRef<Path>,
+    options: &ValidationOptions,
+) -> Result<ValidationResult, ValidationError> {
     let path = path.as_ref();
 
-    let contents = fs::read_to_string(path).map_err(|e| ValidationError {
-        message: format!("Failed to read file: {}", e),
-        is_yaml_error: false,
+    let contents = fs::read_to_string(path).map_err(|e| {
+        ValidationError::single(
+            &rules::UNREADABLE_FILE,
+            format!("Failed to read file: {}", e),
+        )
     })?;
 
-    let model: SemanticModel = serde_yaml::from_str(&contents).map_err(|e| ValidationError {
-        message: format!("Failed to parse YAML file: {}", e),
-        is_yaml_error: true,
+    validate_str_with_options(&contents, &path.display().to_string(), options)
+}
+
+/// Parse a semantic model file without validating it, e.g. to inspect a model that has errors
+pub fn parse_file(path: impl AsRef<Path>) -> Result<SemanticModel, ValidationError> {
+    let path = path.as_ref();
+
+    let contents = fs::read_to_string(path).map_err(|e| {
+        ValidationError::single(
+            &rules::UNREADABLE_FILE,
+            format!("Failed to read file: {}", e),
+        )
     })?;
 
-    // Basic validation
-    if model.name.is_empty() {
-        return Err(ValidationError {
-            message: "Semantic model must have a non-empty 'name' field".to_string(),
-            is_yaml_error: false,
+    serde_yaml::from_str(&contents).map_err(|e| {
+        let is_yaml = yaml_tree::parse(&contents).is_ok();
+        ValidationError {
+            diagnostics: vec![parse_error(
+                &e,
+                is_yaml,
+                &path.display().to_string(),
+                &contents,
+            )],
+        }
+    })
+}
+
+/// The diagnostic for a file that couldn't be deserialized into a model: `INVALID_STRUCTURE` if
+/// it is valid YAML (`is_yaml`), `INVALID_YAML` if not
+fn parse_error(
+    error: &serde_yaml::Error,
+    is_yaml: bool,
+    file_name: &str,
+    contents: &str,
+) -> Diagnostic {
+    let mut diagnostic = if is_yaml {
+        Diagnostic::new(
+            &rules::INVALID_STRUCTURE,
+            format!("Failed to read semantic model: {}", error),
+        )
+    } else {
+        Diagnostic::new(
+            &rules::INVALID_YAML,
+            format!("Failed to parse YAML file: {}", error),
+        )
+    };
+    if let Some(location) = error.location() {
+        diagnostic.location = Some(Location::new(
+            file_name,
+            contents,
+            location.line(),
+            location.column(),
+            1,
+        ));
+    }
+    diagnostic
+}
+
+/// Parse and validate a semantic model read from `reader` (e.g. stdin). `file_name` is only used
+/// to label diagnostics.
+pub fn validate_reader(
+    reader: impl Read,
+    file_name: &str,
+) -> Result<ValidationResult, ValidationError> {
+    validate_reader_with_options(reader, file_name, &ValidationOptions::default())
+}
+
+/// Parse and validate a semantic model read from `reader`, with non-default settings
+pub fn validate_reader_with_options(
+    mut reader: impl Read,
+    file_name: &str,
+    options: &ValidationOptions,
+) -> Result<ValidationResult, ValidationError> {
+    let mut contents = String::new();
+    reader.read_to_string(&mut contents).map_err(|e| {
+        ValidationError::single(
+            &rules::UNREADABLE_FILE,
+            format!("Failed to read {}: {}", file_name, e),
+        )
+    })?;
+
+    validate_str_with_options(&contents, file_name, options)
+}
+
+/// Parse and validate a semantic model held in memory. `file_name` is only used to label
+/// diagnostics.
+pub fn validate_str(contents: &str, file_name: &str) -> Result<ValidationResult, ValidationError> {
+    validate_str_with_options(contents, file_name, &ValidationOptions::default())
+}
+
+/// Parse and validate a semantic model held in memory, with non-default settings
+pub fn validate_str_with_options(
+    contents: &str,
+    file_name: &str,
+    options: &ValidationOptions,
+) -> Result<ValidationResult, ValidationError> {
+    let parsed = yaml_tree::parse(contents);
+    let is_yaml = parsed.is_ok();
+    let tree = parsed.ok().flatten();
+    let mut diagnostics = match (&tree, options.allow_unknown_keys) {
+        (Some(tree), false) => schema::unknown_keys(tree),
+        _ => Vec::new(),
+    };
+
+    let model: SemanticModel = match serde_yaml::from_str(contents) {
+        Ok(model) => model,
+        Err(e) => {
+            let parse_error = parse_error(&e, is_yaml, file_name, contents);
+
+            // A misspelled key often explains a missing field, so report both
+            locate_diagnostics(&mut diagnostics, tree.as_ref(), file_name, contents);
+            // Without a model most rules never ran, so unused suppressions mean nothing yet
+            let mut suppressions = Suppressions::parse(contents, tree.as_ref());
+            suppressions.apply(&mut diagnostics);
+            diagnostics.extend(suppressions.diagnostics(file_name, contents, false));
+            options.apply_rule_levels(&mut diagnostics);
+            if let Some(baseline) = &options.baseline {
+                baseline.apply(file_name, &mut diagnostics);
+            }
+            diagnostics.insert(0, parse_error);
+            return Err(ValidationError { diagnostics });
+        }
+    };
+
+    diagnostics.extend(validate_model(&model));
+    locate_diagnostics(&mut diagnostics, tree.as_ref(), file_name, contents);
+    let mut suppressions = Suppressions::parse(contents, tree.as_ref());
+    suppressions.apply(&mut diagnostics);
+    diagnostics.extend(suppressions.diagnostics(file_name, contents, true));
+    options.apply_rule_levels(&mut diagnostics);
+    if let Some(baseline) = &options.baseline {
+        baseline.apply(file_name, &mut diagnostics);
+    }
+
+    if diagnostics.iter().any(|d| d.is_error()) {
+        return Err(ValidationError { diagnostics });
+    }
+
+    Ok(ValidationResult {
+        model,
+        warnings: diagnostics,
+    })
+}
+
+/// Validate several files in parallel, returning the results in the same order as `paths`
+pub fn validate_files(
+    paths: &[PathBuf],
+    options: &ValidationOptions,
+) -> Vec<Result<ValidationResult, ValidationError>> {
+    paths
+        .par_iter()
+        .map(|path| validate_file_with_options(path, options))
+        .collect()
+}
+
+/// Resolve each diagnostic's path to a line and column in the original source
+fn locate_diagnostics(
+    diagnostics: &mut [Diagnostic],
+    tree: Option<&yaml_tree::Node>,
+    file_name: &str,
+    contents: &str,
+) {
+    let Some(tree) = tree else {
+        return;
+    };
+
+    let locate = |path: &NodePath| {
+        let span = tree.span_at(path);
+        Location::new(file_name, contents, span.line, span.column, span.length)
+    };
+
+    for diagnostic in diagnostics {
+        let within_value = diagnostic.value_span.and_then(|span| {
+            let node = tree.node_at(&diagnostic.path)?;
+            let (line, column) = node.value_position(contents, span.line, span.column)?;
+            Some(Location::new(
+                file_name,
+                contents,
+                line,
+                column,
+                span.length,
+            ))
         });
+        diagnostic.location = Some(within_value.unwrap_or_else(|| locate(&diagnostic.path)));
+        diagnostic.named_path = Some(tree.named_path(&diagnostic.path));
+        for related in &mut diagnostic.related {
+            related.location = Some(locate(&related.path));
+        }
+    }
+}
+
+/// Run every semantic check against a parsed model, collecting all errors and warnings
+pub fn validate_model(model: &SemanticModel) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    if model.name.is_empty() {
+        diagnostics.push(
+            Diagnostic::new(
+                &rules::EMPTY_MODEL_NAME,
+                "Semantic model must have a non-empty 'name' field",
+            )
+            .at(NodePath::root().key("name")),
+        );
     }
 
     if model.tables.is_empty() {
-        return Err(ValidationError {
-            message: "Semantic model must have at least one table".to_string(),
-            is_yaml_error: false,
-        });
+        diagnostics.push(
+            Diagnostic::new(
+                &rules::NO_TABLES,
+                "Semantic model must have at least one table",
+            )
+            .at(NodePath::root().key("tables")),
+        );
     }
 
     for (i, table) in model.tables.iter().enumerate() {
+        let table_path = NodePath::root().key("tables").index(i);
+
         if table.name.is_empty() {
-            return Err(ValidationError {
-                message: format!("Table at index {} must have a non-empty 'name' field", i),
-                is_yaml_error: false,
-            });
+            diagnostics.push(
+                Diagnostic::new(
+                    &rules::EMPTY_TABLE_NAME,
+                    format!("Table at index {} must have a non-empty 'name' field", i),
+                )
+                .at(table_path.key("name")),
+            );
         }
 
         // Validate that each table has at least one dimension, time_dimension, fact, or metric
@@ -259,30 +578,34 @@ pub fn validate_file(path: impl AsRef<Path>) -> Result<ValidationResult, Validat
             && table.facts.is_empty()
             && table.metrics.is_empty()
         {
-            return Err(ValidationError {
-                message: format!(
-                    "Table '{}' must have at least one dimension, time_dimension, fact, or metric",
-                    table.name
-                ),
-                is_yaml_error: false,
-            });
+            diagnostics.push(
+                Diagnostic::new(
+                    &rules::EMPTY_TABLE,
+                    format!(
+                        "Table '{}' must have at least one dimension, time_dimension, fact, or metric",
+                        table.name
+                    ),
+                )
+                .in_table(&table.name)
+                .at(table_path.clone()),
+            );
         }
     }
 
-    // Check for warnings
-    let mut warnings = Vec::new();
-
     // Check if custom_instructions is used without module_custom_instructions
-    if model.custom_instructions.is_some() && model.module_custom_instructions.is_none() {
-        let custom_instructions = model.custom_instructions.as_ref().unwrap();
-        warnings.push(ValidationWarning {
-            message: "The 'custom_instructions' field is deprecated. Consider migrating to 'module_custom_instructions'.".to_string(),
-            suggestion: Some(format!(
-                "Replace:\n  custom_instructions: |\n    {}\n\nWith:\n  module_custom_instructions:\n    sql_generation: |\n      {}",
-                custom_instructions.lines().collect::<Vec<_>>().join("\n    "),
-                custom_instructions.lines().collect::<Vec<_>>().join("\n      ")
-            )),
-        });
+    if let (Some(custom_instructions), None) = (
+        &model.custom_instructions,
+        &model.module_custom_instructions,
+    ) {
+        diagnostics.push(
+            Diagnostic::new(&rules::DEPRECATED_CUSTOM_INSTRUCTIONS, "The 'custom_instructions' field is deprecated. Consider migrating to 'module_custom_instructions'.")
+                .with_suggestion(format!(
+                    "Replace:\n  custom_instructions: |\n    {}\n\nWith:\n  module_custom_instructions:\n    sql_generation: |\n      {}",
+                    custom_instructions.lines().collect::<Vec<_>>().join("\n    "),
+                    custom_instructions.lines().collect::<Vec<_>>().join("\n      ")
+                ))
+                .at(NodePath::root().key("custom_instructions")),
+        );
     }
 
     // Validate module_custom_instructions if present
@@ -290,52 +613,1258 @@ pub fn validate_file(path: impl AsRef<Path>) -> Result<ValidationResult, Validat
         if module_instructions.question_categorization.is_none()
             && module_instructions.sql_generation.is_none()
         {
-            return Err(ValidationError {
-                message: "'module_custom_instructions' must have at least one of 'question_categorization' or 'sql_generation' defined".to_string(),
-                is_yaml_error: false,
-            });
+            diagnostics.push(
+                Diagnostic::new(&rules::EMPTY_MODULE_INSTRUCTIONS, "'module_custom_instructions' must have at least one of 'question_categorization' or 'sql_generation' defined")
+                    .at(NodePath::root().key("module_custom_instructions")),
+            );
+        }
+    }
+
+    let graph = DependencyGraph::new(model);
+    validate_data_types(model, &mut diagnostics);
+    validate_spec_values(model, &mut diagnostics);
+    validate_expressions(model, &graph, &mut diagnostics);
+    validate_metric_dependencies(model, &graph, &mut diagnostics);
+    validate_unique_names(model, &mut diagnostics);
+    validate_relationships(model, &mut diagnostics);
+
+    diagnostics
+}
+
+/// Check that a `data_type` is a valid Snowflake type, returning the parsed type if it is
+fn check_data_type(
+    data_type: &str,
+    table: Option<&str>,
+    path: NodePath,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<SnowflakeType> {
+    let mut diagnostic = match SnowflakeType::parse(data_type) {
+        Ok(parsed) => return Some(parsed),
+        Err(e @ DataTypeError::UnknownType(_)) => {
+            let name = data_type.split('(').next().unwrap_or(data_type).trim();
+            let diagnostic = Diagnostic::new(&rules::UNKNOWN_DATA_TYPE, e.to_string());
+            match suggest::closest_match(name, data_type::TYPE_NAMES.iter().copied()) {
+                Some(candidate) => {
+                    diagnostic.with_suggestion(format!("Did you mean '{}'?", candidate))
+                }
+                None => diagnostic,
+            }
+        }
+        Err(DataTypeError::InvalidParameters { message, .. }) => Diagnostic::new(
+            &rules::INVALID_TYPE_PARAMETERS,
+            format!("Invalid data_type '{}': {}", data_type, message),
+        ),
+    }
+    .at(path);
+    if let Some(table) = table {
+        diagnostic = diagnostic.in_table(table);
+    }
+    diagnostics.push(diagnostic);
+    None
+}
+
+/// Check that fields with a fixed set of values in the spec have one of them
+fn validate_spec_values(model: &SemanticModel, diagnostics: &mut Vec<Diagnostic>) {
+    for (i, table) in model.tables.iter().enumerate() {
+        let table_path = NodePath::root().key("tables").index(i);
+        let in_table = Some(table.name.as_str());
+
+        for (j, fact) in table.facts.iter().enumerate() {
+            let path = table_path.key("facts").index(j);
+            if let Some(aggregation) = &fact.aggregation {
+                check_spec_value(aggregation, in_table, path.key("aggregation"), diagnostics);
+            }
+            if let Some(access) = &fact.access_modifier {
+                check_spec_value(access, in_table, path.key("access_modifier"), diagnostics);
+            }
+        }
+        for (j, metric) in table.metrics.iter().enumerate() {
+            if let Some(access) = &metric.access_modifier {
+                let path = table_path.key("metrics").index(j).key("access_modifier");
+                check_spec_value(access, in_table, path, diagnostics);
+            }
+        }
+    }
+
+    for (i, metric) in model.metrics.iter().enumerate() {
+        if let Some(access) = &metric.access_modifier {
+            let path = NodePath::root()
+                .key("metrics")
+                .index(i)
+                .key("access_modifier");
+            check_spec_value(access, None, path, diagnostics);
+        }
+    }
+
+    for (i, relationship) in model.relationships.iter().enumerate() {
+        let path = NodePath::root().key("relationships").index(i);
+        check_spec_value(
+            &relationship.join_type,
+            None,
+            path.key("join_type"),
+            diagnostics,
+        );
+        check_spec_value(
+            &relationship.relationship_type,
+            None,
+            path.key("relationship_type"),
+            diagnostics,
+        );
+    }
+}
+
+/// Report `value` if it isn't one the spec allows
+fn check_spec_value<T: SpecEnum>(
+    value: &T,
+    table: Option<&str>,
+    path: NodePath,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(name) = value.unknown_name() else {
+        return;
+    };
+
+    let allowed: Vec<&str> = T::names().collect();
+    let mut diagnostic = Diagnostic::new(
+        &rules::INVALID_VALUE,
+        format!(
+            "Invalid {} '{}'. Allowed values: {}",
+            T::FIELD,
+            name,
+            allowed.join(", ")
+        ),
+    )
+    .at(path)
+    .within_value(Span {
+        line: 1,
+        column: 1,
+        length: name.chars().count(),
+    });
+    if let Some(table) = table {
+        diagnostic = diagnostic.in_table(table);
+    }
+    if let Some(candidate) = suggest::closest_match(name, T::names()) {
+        diagnostic = diagnostic.with_suggestion(format!("Did you mean '{}'?", candidate));
+    }
+    diagnostics.push(diagnostic);
+}
+
+/// Suggested YAML for declaring `dimension` as a time dimension instead
+fn time_dimension_rewrite(dimension: &Dimension) -> String {
+    let mut yaml = format!(
+        "Move it from 'dimensions' to 'time_dimensions':\n  time_dimensions:\n    - name: {}\n      expr: {}\n      data_type: {}",
+        dimension.name, dimension.expr, dimension.data_type
+    );
+    if let Some(description) = &dimension.description {
+        yaml.push_str(&format!("\n      description: {}", description));
+    }
+    if let Some(synonyms) = &dimension.synonyms {
+        yaml.push_str("\n      synonyms:");
+        for synonym in synonyms {
+            yaml.push_str(&format!("\n        - {}", synonym));
+        }
+    }
+    yaml
+}
+
+/// Check every column and metric `data_type` in the model, and that time dimensions (and only
+/// time dimensions) are temporal
+fn validate_data_types(model: &SemanticModel, diagnostics: &mut Vec<Diagnostic>) {
+    for (i, table) in model.tables.iter().enumerate() {
+        let table_path = NodePath::root().key("tables").index(i);
+        let in_table = Some(table.name.as_str());
+        let data_type_path = |key: &str, j: usize| table_path.key(key).index(j).key("data_type");
+
+        for (j, dimension) in table.dimensions.iter().enumerate() {
+            let path = data_type_path("dimensions", j);
+            let parsed = check_data_type(&dimension.data_type, in_table, path.clone(), diagnostics);
+            if parsed.is_some_and(|t| t.is_temporal()) {
+                diagnostics.push(
+                    Diagnostic::new(
+                        &rules::TEMPORAL_DIMENSION,
+                        format!(
+                            "Dimension '{}' has temporal data_type '{}' and should be a time dimension",
+                            dimension.name, dimension.data_type
+                        ),
+                    )
+                    .with_suggestion(time_dimension_rewrite(dimension))
+                    .in_table(&table.name)
+                    .at(path),
+                );
+            }
+        }
+        for (j, time_dimension) in table.time_dimensions.iter().enumerate() {
+            let path = data_type_path("time_dimensions", j);
+            let parsed = check_data_type(
+                &time_dimension.data_type,
+                in_table,
+                path.clone(),
+                diagnostics,
+            );
+            if parsed.is_some_and(|t| !t.is_temporal()) {
+                diagnostics.push(
+                    Diagnostic::new(
+                        &rules::NON_TEMPORAL_TIME_DIMENSION,
+                        format!(
+                            "Time dimension '{}' has data_type '{}', which is not a date or time type",
+                            time_dimension.name, time_dimension.data_type
+                        ),
+                    )
+                    .with_suggestion(
+                        "Use DATE, TIME, or one of TIMESTAMP_LTZ, TIMESTAMP_NTZ and TIMESTAMP_TZ.\n\
+                         If the column doesn't hold dates or times, move it to 'dimensions'.",
+                    )
+                    .in_table(&table.name)
+                    .at(path),
+                );
+            }
+        }
+        for (j, fact) in table.facts.iter().enumerate() {
+            let path = data_type_path("facts", j);
+            check_data_type(&fact.data_type, in_table, path, diagnostics);
+        }
+        for (j, metric) in table.metrics.iter().enumerate() {
+            if let Some(data_type) = &metric.data_type {
+                let path = data_type_path("metrics", j);
+                check_data_type(data_type, in_table, path, diagnostics);
+            }
+        }
+    }
+
+    for (i, metric) in model.metrics.iter().enumerate() {
+        if let Some(data_type) = &metric.data_type {
+            let path = NodePath::root().key("metrics").index(i).key("data_type");
+            check_data_type(data_type, None, path, diagnostics);
+        }
+    }
+}
+
+/// An `expr` in the model, with what it belongs to
+struct ExprEntry<'a> {
+    kind: ExprKind,
+    name: &'a str,
+    /// The table the expression is defined in, or `None` for model-level metrics
+    table: Option<&'a Table>,
+    expr: &'a str,
+    /// Path of the `expr` key
+    path: NodePath,
+}
+
+impl ExprEntry<'_> {
+    /// A diagnostic about this expression
+    fn diagnostic(&self, rule: &'static Rule, message: impl Into<String>) -> Diagnostic {
+        let diagnostic = Diagnostic::new(rule, message).at(self.path.clone());
+        match self.table {
+            Some(table) => diagnostic.in_table(&table.name),
+            None => diagnostic,
+        }
+    }
+}
+
+/// Every `expr` in the model: the columns, metrics and filters of each table, then the
+/// model-level metrics
+fn model_expressions(model: &SemanticModel) -> Vec<ExprEntry<'_>> {
+    let mut entries = Vec::new();
+
+    for (i, table) in model.tables.iter().enumerate() {
+        let table_path = NodePath::root().key("tables").index(i);
+        let entry = |kind, key: &str, j: usize, name, expr| ExprEntry {
+            kind,
+            name,
+            table: Some(table),
+            expr,
+            path: table_path.key(key).index(j).key("expr"),
+        };
+
+        entries.extend(
+            table
+                .dimensions
+                .iter()
+                .enumerate()
+                .map(|(j, d)| entry(ExprKind::Dimension, "dimensions", j, &d.name, &d.expr)),
+        );
+        entries.extend(table.time_dimensions.iter().enumerate().map(|(j, d)| {
+            entry(
+                ExprKind::TimeDimension,
+                "time_dimensions",
+                j,
+                &d.name,
+                &d.expr,
+            )
+        }));
+        entries.extend(
+            table
+                .facts
+                .iter()
+                .enumerate()
+                .map(|(j, f)| entry(ExprKind::Fact, "facts", j, &f.name, &f.expr)),
+        );
+        entries.extend(
+            table
+                .metrics
+                .iter()
+                .enumerate()
+                .map(|(j, m)| entry(ExprKind::Metric, "metrics", j, &m.name, &m.expr)),
+        );
+        entries.extend(
+            table
+                .filters
+                .iter()
+                .enumerate()
+                .map(|(j, f)| entry(ExprKind::Filter, "filters", j, &f.name, &f.expr)),
+        );
+    }
+
+    entries.extend(
+        model
+            .metrics
+            .iter()
+            .enumerate()
+            .map(|(i, metric)| ExprEntry {
+                kind: ExprKind::Metric,
+                name: &metric.name,
+                table: None,
+                expr: &metric.expr,
+                path: NodePath::root().key("metrics").index(i).key("expr"),
+            }),
+    );
+
+    entries
+}
+
+/// Check that every `expr` parses as a single Snowflake SQL expression, that metrics and filters
+/// only refer to names defined in the model, and that only metrics aggregate
+fn validate_expressions(
+    model: &SemanticModel,
+    graph: &DependencyGraph,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for entry in model_expressions(model) {
+        if entry.expr.trim().is_empty() {
+            diagnostics.push(entry.diagnostic(
+                &rules::INVALID_EXPRESSION,
+                format!(
+                    "The expr of {} '{}' is empty",
+                    entry.kind.label(),
+                    entry.name
+                ),
+            ));
+            continue;
+        }
+
+        let expr = match sql_expr::parse(entry.expr) {
+            Ok(expr) => expr,
+            Err(e) => {
+                diagnostics.push(
+                    entry
+                        .diagnostic(
+                            &rules::INVALID_EXPRESSION,
+                            format!(
+                                "Invalid SQL in the expr of {} '{}': {}",
+                                entry.kind.label(),
+                                entry.name,
+                                e
+                            ),
+                        )
+                        .within_value(e.span),
+                );
+                continue;
+            }
+        };
+
+        let analysis = sql_expr::analyze(&expr);
+        // Dimensions, time dimensions and facts are defined over the physical columns of the base
+        // table, which the model doesn't describe
+        if matches!(entry.kind, ExprKind::Metric | ExprKind::Filter) {
+            check_references(model, &entry, &analysis.references, diagnostics);
+        }
+        check_aggregation(graph, &entry, &analysis, diagnostics);
+    }
+}
+
+/// The names of a table's columns and metrics, which its metrics and filters can refer to
+fn referenceable_names(table: &Table) -> impl Iterator<Item = &str> + Clone {
+    joinable_column_names(table).chain(table.metrics.iter().map(|m| m.name.as_str()))
+}
+
+/// Report the names in `expr` that don't resolve. Within a table, unqualified names refer to the
+/// table's own columns and metrics. Model-level metrics can use the metrics of any table, or of
+/// the model, unqualified. `TABLE.NAME` refers to a column or metric of another table.
+fn check_references(
+    model: &SemanticModel,
+    entry: &ExprEntry,
+    references: &[sql_expr::Reference],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for reference in references {
+        let is = |name: &str| reference.part_is(0, name);
+        let first = &reference.parts[0].value;
+
+        // The part of the name that doesn't resolve, why, and what might have been meant
+        let (part, detail, suggestion) = match (reference.parts.len(), entry.table) {
+            (1, Some(table)) => {
+                let names = referenceable_names(table);
+                if names.clone().any(is) {
+                    continue;
+                }
+                (
+                    0,
+                    format!(
+                        "table '{}' has no column or metric with that name",
+                        table.name
+                    ),
+                    suggest::did_you_mean(first, "columns and metrics", names),
+                )
+            }
+            (1, None) => {
+                let metrics = model
+                    .tables
+                    .iter()
+                    .flat_map(|t| &t.metrics)
+                    .chain(&model.metrics)
+                    .map(|m| m.name.as_str());
+                if metrics.clone().any(is) {
+                    continue;
+                }
+                let column_of = model.tables.iter().find(|t| referenceable_names(t).any(is));
+                let suggestion = match column_of {
+                    Some(table) => Some(format!(
+                        "Columns must be qualified with their table, e.g. '{}.{}'",
+                        table.name, first
+                    )),
+                    None => suggest::did_you_mean(first, "metrics", metrics),
+                };
+                (
+                    0,
+                    "no table or model-level metric has that name".to_string(),
+                    suggestion,
+                )
+            }
+            (2, _) => match model.tables.iter().find(|t| is(&t.name)) {
+                None => {
+                    let tables = model.tables.iter().map(|t| t.name.as_str());
+                    (
+                        0,
+                        "there is no table with that name".to_string(),
+                        suggest::did_you_mean(first, "tables", tables),
+                    )
+                }
+                Some(table) => {
+                    let names = referenceable_names(table);
+                    if names.clone().any(|name| reference.part_is(1, name)) {
+                        continue;
+                    }
+                    let column = &reference.parts[1].value;
+                    (
+                        1,
+                        format!(
+                            "table '{}' has no column or metric named '{}'",
+                            table.name, column
+                        ),
+                        suggest::did_you_mean(column, "columns and metrics", names),
+                    )
+                }
+            },
+            // Longer names, like paths into semi-structured columns, can't be checked
+            _ => continue,
+        };
+
+        let mut diagnostic = entry
+            .diagnostic(
+                &rules::UNKNOWN_REFERENCE,
+                format!(
+                    "Unknown reference '{}' in the expr of {} '{}': {}",
+                    reference,
+                    entry.kind.label(),
+                    entry.name,
+                    detail
+                ),
+            )
+            .within_value(reference.span(part));
+        if let Some(suggestion) = suggestion {
+            diagnostic = diagnostic.with_suggestion(suggestion);
+        }
+        diagnostics.push(diagnostic);
+    }
+}
+
+/// Whether `reference`, in the expr of `entry`, names a metric. A column takes precedence over a
+/// metric with the same name.
+fn refers_to_metric(
+    graph: &DependencyGraph,
+    entry: &ExprEntry,
+    reference: &sql_expr::Reference,
+) -> bool {
+    graph
+        .resolve(entry.table.map(|table| table.name.as_str()), reference)
+        .is_some_and(|item| graph.items()[item].kind == ExprKind::Metric)
+}
+
+/// Check that metrics aggregate, without nesting aggregates, and that dimensions, time dimensions
+/// and facts, which are evaluated for each row, don't
+fn check_aggregation(
+    graph: &DependencyGraph,
+    entry: &ExprEntry,
+    analysis: &sql_expr::Analysis,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let kind = entry.kind.label();
+
+    match entry.kind {
+        ExprKind::Dimension | ExprKind::TimeDimension | ExprKind::Fact => {
+            for call in &analysis.calls {
+                let (function, suggestion) = match call.kind {
+                    sql_expr::CallKind::Aggregate => (
+                        "aggregate function",
+                        format!(
+                            "Define it as a metric instead:\n  metrics:\n    - name: {}\n      expr: {}",
+                            entry.name, entry.expr
+                        ),
+                    ),
+                    sql_expr::CallKind::Window => (
+                        "window function",
+                        "Compute the value in the base table, or in a view used as the base table"
+                            .to_string(),
+                    ),
+                };
+                diagnostics.push(
+                    entry
+                        .diagnostic(
+                            &rules::ROW_LEVEL_AGGREGATE,
+                            format!(
+                                "The expr of {} '{}' uses {} {}, but {}s are evaluated for each row",
+                                kind, entry.name, function, call.name, kind
+                            ),
+                        )
+                        .with_suggestion(suggestion)
+                        .within_value(call.span),
+                );
+            }
+        }
+        ExprKind::Metric => {
+            for call in &analysis.calls {
+                let (sql_expr::CallKind::Aggregate, Some(outer)) = (call.kind, &call.aggregated_by)
+                else {
+                    continue;
+                };
+                diagnostics.push(
+                    entry
+                        .diagnostic(
+                            &rules::NESTED_AGGREGATE,
+                            format!(
+                                "The expr of metric '{}' nests aggregate function {} inside {}",
+                                entry.name, call.name, outer
+                            ),
+                        )
+                        .with_suggestion(
+                            "Aggregate functions can't be nested. Aggregate the column directly, or \
+                             pre-aggregate it in a view used as the base table.",
+                        )
+                        .within_value(call.span),
+                );
+            }
+
+            let metrics: Vec<&sql_expr::Reference> = analysis
+                .references
+                .iter()
+                .filter(|reference| refers_to_metric(graph, entry, reference))
+                .collect();
+            for reference in &metrics {
+                let Some(outer) = &reference.aggregated_by else {
+                    continue;
+                };
+                diagnostics.push(
+                    entry
+                        .diagnostic(
+                            &rules::NESTED_AGGREGATE,
+                            format!(
+                                "The expr of metric '{}' aggregates metric '{}' with {}, but it is already aggregated",
+                                entry.name, reference, outer
+                            ),
+                        )
+                        .with_suggestion(format!(
+                            "Use '{}' directly, outside of {}",
+                            reference, outer
+                        ))
+                        .within_value(reference.span(reference.parts.len() - 1)),
+                );
+            }
+
+            if !analysis.aggregates() && metrics.is_empty() {
+                diagnostics.push(
+                    entry
+                        .diagnostic(
+                            &rules::METRIC_WITHOUT_AGGREGATE,
+                            format!(
+                                "The expr of metric '{}' doesn't use an aggregate function",
+                                entry.name
+                            ),
+                        )
+                        .with_suggestion(format!(
+                            "Metrics combine the rows of each group into one value. Aggregate it, e.g. \
+                             SUM({}), or make it a fact or dimension instead.",
+                            entry.expr.trim()
+                        )),
+                );
+            }
+        }
+        // Filters are conditions on rows, but Snowflake doesn't restrict what they call
+        ExprKind::Filter => {}
+    }
+}
+
+/// Check that no metric depends on itself, and that the tables each metric uses are connected by
+/// relationships
+fn validate_metric_dependencies(
+    model: &SemanticModel,
+    graph: &DependencyGraph,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let items = graph.items();
+    let diagnostic = |item: &GraphItem, rule: &'static Rule, message: String| {
+        let diagnostic = Diagnostic::new(rule, message).at(item.path.key("expr"));
+        match &item.table {
+            Some(table) => diagnostic.in_table(table),
+            None => diagnostic,
+        }
+    };
+
+    for cycle in graph.cycles() {
+        let first = &items[cycle[0]];
+        let path: Vec<String> = cycle.iter().map(|&i| items[i].qualified_name()).collect();
+        let mut cycle_diagnostic = diagnostic(
+            first,
+            &rules::METRIC_CYCLE,
+            format!(
+                "The expr of metric '{}' depends on itself: {}",
+                first.name,
+                path.join(" -> ")
+            ),
+        )
+        .with_suggestion(if cycle.len() == 2 {
+            format!("Define '{}' without referring to itself", first.name)
+        } else {
+            "Define one of the metrics in the cycle without the others".to_string()
+        });
+        for &i in &cycle[1..cycle.len() - 1] {
+            cycle_diagnostic = cycle_diagnostic.with_related(
+                items[i].path.key("expr"),
+                format!("'{}' is part of the cycle", items[i].qualified_name()),
+            );
+        }
+        diagnostics.push(cycle_diagnostic);
+    }
+
+    for (i, item) in items.iter().enumerate() {
+        if item.kind != ExprKind::Metric {
+            continue;
+        }
+        let tables = graph.tables_used(i);
+        let Some((start, unconnected)) = unconnected_tables(model, item.table.as_deref(), &tables)
+        else {
+            continue;
+        };
+        // Only report where the tables are first combined, not every metric built from it
+        let reported_in_dependency = graph.dependencies(i).iter().any(|&dependency| {
+            items[dependency].kind == ExprKind::Metric
+                && unconnected_tables(model, None, &graph.tables_used(dependency)).is_some()
+        });
+        if reported_in_dependency {
+            continue;
+        }
+
+        diagnostics.push(
+            diagnostic(
+                item,
+                &rules::DISCONNECTED_METRIC_TABLES,
+                format!(
+                    "The expr of metric '{}' uses {}, but no relationships connect {} to '{}'",
+                    item.name,
+                    quoted_list(tables.iter().map(String::as_str)),
+                    quoted_list(unconnected.iter().copied()),
+                    start
+                ),
+            )
+            .with_suggestion(format!(
+                "Add a relationship between '{}' and '{}', or between tables that connect them",
+                start, unconnected[0]
+            )),
+        );
+    }
+}
+
+/// If relationships don't connect all of `tables`, the table they were followed from (`own`, or
+/// else the first table) and the tables they don't reach from it
+fn unconnected_tables<'a>(
+    model: &SemanticModel,
+    own: Option<&'a str>,
+    tables: &'a BTreeSet<String>,
+) -> Option<(&'a str, Vec<&'a str>)> {
+    let start = own.or_else(|| tables.iter().next().map(String::as_str))?;
+
+    let mut reached = vec![start.to_ascii_lowercase()];
+    let mut next = 0;
+    while let Some(table) = reached.get(next).cloned() {
+        next += 1;
+        for relationship in &model.relationships {
+            let other = if relationship.left_table.eq_ignore_ascii_case(&table) {
+                &relationship.right_table
+            } else if relationship.right_table.eq_ignore_ascii_case(&table) {
+                &relationship.left_table
+            } else {
+                continue;
+            };
+            let other = other.to_ascii_lowercase();
+            if !reached.contains(&other) {
+                reached.push(other);
+            }
+        }
+    }
+
+    let unconnected: Vec<&str> = tables
+        .iter()
+        .filter(|table| !reached.contains(&table.to_ascii_lowercase()))
+        .map(String::as_str)
+        .collect();
+    (!unconnected.is_empty()).then_some((start, unconnected))
+}
+
+/// Quote and list names, e.g. `'A', 'B' and 'C'`
+fn quoted_list<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    let names: Vec<String> = names.map(|name| format!("'{}'", name)).collect();
+    match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => names.concat(),
+    }
+}
+
+/// A named entry in the model, for uniqueness checks
+struct NamedEntry<'a> {
+    /// What the entry is, e.g. "time dimension"
+    kind: &'static str,
+    name: &'a str,
+    table: Option<&'a str>,
+    path: NodePath,
+}
+
+impl<'a> NamedEntry<'a> {
+    /// An entry whose `name` key is at `item`
+    fn new(kind: &'static str, name: &'a str, table: Option<&'a str>, item: NodePath) -> Self {
+        NamedEntry {
+            kind,
+            name,
+            table,
+            path: item.key("name"),
         }
     }
 
-    Ok(ValidationResult { model, warnings })
+    fn describe(&self) -> String {
+        match self.table {
+            Some(table) => format!("{} '{}' in table '{}'", self.kind, self.name, table),
+            None => format!("{} '{}'", self.kind, self.name),
+        }
+    }
+}
+
+/// Report every entry whose name (ignoring case) was already used by an earlier entry
+fn check_unique_names<'a>(
+    entries: impl IntoIterator<Item = NamedEntry<'a>>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut seen: Vec<NamedEntry> = Vec::new();
+
+    for entry in entries {
+        // Empty names are reported by their own checks
+        if entry.name.is_empty() {
+            continue;
+        }
+
+        let Some(first) = seen
+            .iter()
+            .find(|first| first.name.eq_ignore_ascii_case(entry.name))
+        else {
+            seen.push(entry);
+            continue;
+        };
+
+        let mut kind = entry.kind.to_string();
+        kind[..1].make_ascii_uppercase();
+        let mut diagnostic = Diagnostic::new(
+            &rules::DUPLICATE_NAME,
+            format!(
+                "{} '{}' has the same name as {}",
+                kind,
+                entry.name,
+                first.describe()
+            ),
+        )
+        .with_suggestion("Rename one of them. Names are compared case-insensitively.")
+        .with_related(
+            first.path.clone(),
+            format!("{} is defined here", first.describe()),
+        )
+        .at(entry.path);
+        if let Some(table) = entry.table {
+            diagnostic = diagnostic.in_table(table);
+        }
+        diagnostics.push(diagnostic);
+    }
+}
+
+/// Check that names are unique within the scope Snowflake resolves them in
+fn validate_unique_names(model: &SemanticModel, diagnostics: &mut Vec<Diagnostic>) {
+    let root = NodePath::root();
+
+    let entry = NamedEntry::new;
+    check_unique_names(
+        model
+            .tables
+            .iter()
+            .enumerate()
+            .map(|(i, table)| entry("table", &table.name, None, root.key("tables").index(i))),
+        diagnostics,
+    );
+
+    let mut table_metrics = Vec::new();
+    for (i, table) in model.tables.iter().enumerate() {
+        let table_path = root.key("tables").index(i);
+        let in_table = Some(table.name.as_str());
+        let list = |key: &str, j: usize| table_path.key(key).index(j);
+
+        let columns = table
+            .dimensions
+            .iter()
+            .enumerate()
+            .map(|(j, d)| entry("dimension", &d.name, in_table, list("dimensions", j)))
+            .chain(table.time_dimensions.iter().enumerate().map(|(j, d)| {
+                entry(
+                    "time dimension",
+                    &d.name,
+                    in_table,
+                    list("time_dimensions", j),
+                )
+            }))
+            .chain(
+                table
+                    .facts
+                    .iter()
+                    .enumerate()
+                    .map(|(j, f)| entry("fact", &f.name, in_table, list("facts", j))),
+            );
+        let metrics = table
+            .metrics
+            .iter()
+            .enumerate()
+            .map(|(j, m)| entry("metric", &m.name, in_table, list("metrics", j)));
+        check_unique_names(columns.chain(metrics.clone()), diagnostics);
+        table_metrics.extend(metrics);
+
+        check_unique_names(
+            table
+                .filters
+                .iter()
+                .enumerate()
+                .map(|(j, f)| entry("filter", &f.name, in_table, list("filters", j))),
+            diagnostics,
+        );
+    }
+
+    // Model-level metrics share a namespace with each other and with every table's metrics
+    check_unique_names(
+        table_metrics
+            .into_iter()
+            .chain(model.metrics.iter().enumerate().map(|(i, m)| {
+                entry(
+                    "model-level metric",
+                    &m.name,
+                    None,
+                    root.key("metrics").index(i),
+                )
+            })),
+        diagnostics,
+    );
+
+    check_unique_names(
+        model.relationships.iter().enumerate().map(|(i, r)| {
+            entry(
+                "relationship",
+                &r.name,
+                None,
+                root.key("relationships").index(i),
+            )
+        }),
+        diagnostics,
+    );
+
+    check_unique_names(
+        model.verified_queries.iter().enumerate().map(|(i, q)| {
+            entry(
+                "verified query",
+                &q.name,
+                None,
+                root.key("verified_queries").index(i),
+            )
+        }),
+        diagnostics,
+    );
+}
+
+/// Find a table by name, ignoring case as Snowflake does for unquoted identifiers
+fn find_table<'a>(model: &'a SemanticModel, name: &str) -> Option<&'a Table> {
+    model
+        .tables
+        .iter()
+        .find(|table| table.name.eq_ignore_ascii_case(name))
+}
+
+/// The `name` and `expr` of each dimension, time dimension and fact of a table, which
+/// relationships can join on
+fn joinable_columns(table: &Table) -> impl Iterator<Item = (&str, &str)> + Clone {
+    table
+        .dimensions
+        .iter()
+        .map(|d| (d.name.as_str(), d.expr.as_str()))
+        .chain(
+            table
+                .time_dimensions
+                .iter()
+                .map(|d| (d.name.as_str(), d.expr.as_str())),
+        )
+        .chain(
+            table
+                .facts
+                .iter()
+                .map(|f| (f.name.as_str(), f.expr.as_str())),
+        )
+}
+
+/// Names of the dimensions, time dimensions and facts of a table. Besides being joinable, these
+/// are the columns a table's metrics and filters can refer to.
+fn joinable_column_names(table: &Table) -> impl Iterator<Item = &str> + Clone {
+    joinable_columns(table).map(|(name, _)| name)
+}
+
+/// The name of the joinable column of `table` that `column` names (or is the expression of)
+fn find_joinable_column<'a>(table: &'a Table, column: &str) -> Option<&'a str> {
+    joinable_columns(table)
+        .find(|(name, expr)| name.eq_ignore_ascii_case(column) || expr.eq_ignore_ascii_case(column))
+        .map(|(name, _)| name)
+}
+
+/// The canonical name for a column reference, resolving `expr`s to the column's `name`
+fn canonical_column_name(table: &Table, column: &str) -> String {
+    find_joinable_column(table, column)
+        .unwrap_or(column)
+        .to_uppercase()
+}
+
+/// Check that the right-hand columns of a relationship are exactly the right table's primary key.
+///
+/// Both relationship types (many_to_one and one_to_one) require this.
+fn validate_relationship_key(
+    relationship: &Relationship,
+    right_table: &Table,
+    relationship_path: &NodePath,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let right_columns: Vec<&str> = relationship
+        .relationship_columns
+        .iter()
+        .map(|c| c.right_column.as_str())
+        .collect();
+
+    let Some(primary_key) = &right_table.primary_key else {
+        diagnostics.push(
+            Diagnostic::new(
+                &rules::MISSING_PRIMARY_KEY,
+                format!(
+                    "Relationship '{}' is {} but its right_table '{}' has no primary_key",
+                    relationship.name, relationship.relationship_type, right_table.name
+                ),
+            )
+            .with_suggestion(format!(
+                "Add a primary key to table '{}':\n  primary_key:\n    columns:\n      - {}",
+                right_table.name,
+                right_columns.join("\n      - ")
+            ))
+            .at(relationship_path.key("right_table")),
+        );
+        return;
+    };
+
+    let joined: BTreeSet<String> = right_columns
+        .iter()
+        .map(|c| canonical_column_name(right_table, c))
+        .collect();
+    let key: BTreeSet<String> = primary_key
+        .columns
+        .iter()
+        .map(|c| canonical_column_name(right_table, c))
+        .collect();
+
+    if joined == key {
+        return;
+    }
+
+    let mut details = Vec::new();
+    let missing: Vec<&str> = key.difference(&joined).map(String::as_str).collect();
+    if !missing.is_empty() {
+        details.push(format!(
+            "Join on the missing key columns: {}",
+            missing.join(", ")
+        ));
+    }
+    let extra: Vec<&str> = joined.difference(&key).map(String::as_str).collect();
+    if !extra.is_empty() {
+        details.push(format!(
+            "Remove the columns that aren't part of the key: {}",
+            extra.join(", ")
+        ));
+    }
+
+    diagnostics.push(
+        Diagnostic::new(
+            &rules::RELATIONSHIP_KEY_MISMATCH,
+            format!(
+                "Relationship '{}' joins on ({}), but the primary key of table '{}' is ({})",
+                relationship.name,
+                right_columns.join(", "),
+                right_table.name,
+                primary_key.columns.join(", ")
+            ),
+        )
+        .with_suggestion(details.join("\n"))
+        .at(relationship_path.key("relationship_columns")),
+    );
 }
 
-/// Format a validation error as a ColoredDoc
+/// Check that relationships only refer to tables and columns that exist
+fn validate_relationships(model: &SemanticModel, diagnostics: &mut Vec<Diagnostic>) {
+    let table_names: Vec<&str> = model.tables.iter().map(|t| t.name.as_str()).collect();
+
+    for (i, relationship) in model.relationships.iter().enumerate() {
+        let relationship_path = NodePath::root().key("relationships").index(i);
+
+        let mut resolve_table = |key: &str, name: &str| {
+            let table = find_table(model, name);
+            if table.is_none() {
+                let mut diagnostic = Diagnostic::new(
+                    &rules::UNKNOWN_RELATIONSHIP_TABLE,
+                    format!(
+                        "Relationship '{}' has {} '{}', which is not a table in this model",
+                        relationship.name, key, name
+                    ),
+                )
+                .at(relationship_path.key(key));
+                if let Some(suggestion) =
+                    suggest::did_you_mean(name, "tables", table_names.iter().copied())
+                {
+                    diagnostic = diagnostic.with_suggestion(suggestion);
+                }
+                diagnostics.push(diagnostic);
+            }
+            table
+        };
+        let left_table = resolve_table("left_table", &relationship.left_table);
+        let right_table = resolve_table("right_table", &relationship.right_table);
+
+        for (j, columns) in relationship.relationship_columns.iter().enumerate() {
+            let columns_path = relationship_path.key("relationship_columns").index(j);
+            let sides = [
+                ("left_column", &columns.left_column, left_table),
+                ("right_column", &columns.right_column, right_table),
+            ];
+
+            for (key, column, table) in sides {
+                // Unknown tables have already been reported
+                let Some(table) = table else {
+                    continue;
+                };
+                if find_joinable_column(table, column).is_some() {
+                    continue;
+                }
+
+                let mut diagnostic = Diagnostic::new(
+                    &rules::UNKNOWN_RELATIONSHIP_COLUMN,
+                    format!(
+                        "Relationship '{}' has {} '{}', which is not a dimension, time dimension or fact of table '{}'",
+                        relationship.name, key, column, table.name
+                    ),
+                )
+                .at(columns_path.key(key));
+                if let Some(suggestion) =
+                    suggest::did_you_mean(column, "columns", joinable_column_names(table))
+                {
+                    diagnostic = diagnostic.with_suggestion(suggestion);
+                }
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        if let Some(right_table) = right_table {
+            validate_relationship_key(relationship, right_table, &relationship_path, diagnostics);
+        }
+    }
+}
+
+pub(crate) fn pluralize(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// Format a rustc-style source snippet, underlining the offending text with `marker`
+fn format_snippet(location: &Location, marker: char, color: Color) -> ColoredDoc {
+    let line_number = location.line.to_string();
+    let gutter = " ".repeat(line_number.len());
+    let gutter_spec = color_spec(Color::Blue, true);
+
+    ColoredDoc::concat(vec![
+        ColoredDoc::colored_text(format!("  {}--> ", gutter), gutter_spec.clone()),
+        ColoredDoc::text(format!(
+            "{}:{}:{}",
+            location.file, location.line, location.column
+        )),
+        ColoredDoc::line(),
+        ColoredDoc::colored_text(format!("  {} |", gutter), gutter_spec.clone()),
+        ColoredDoc::line(),
+        ColoredDoc::colored_text(format!("  {} | ", line_number), gutter_spec.clone()),
+        ColoredDoc::text(&location.source_line),
+        ColoredDoc::line(),
+        ColoredDoc::colored_text(format!("  {} | ", gutter), gutter_spec),
+        ColoredDoc::text(" ".repeat(location.column.saturating_sub(1))),
+        ColoredDoc::colored_text(
+            marker.to_string().repeat(location.length.max(1)),
+            color_spec(color, true),
+        ),
+        ColoredDoc::line(),
+    ])
+}
+
+/// Format a single diagnostic, with its source snippet and suggestion if there are any
+fn format_diagnostic(diagnostic: &Diagnostic) -> ColoredDoc {
+    let color = if diagnostic.is_error() {
+        Color::Red
+    } else {
+        Color::Yellow
+    };
+
+    let mut doc = ColoredDoc::colored_text(
+        format!(
+            "* {}[{}]: ",
+            diagnostic.severity.label(),
+            diagnostic.rule.code
+        ),
+        color_spec(color, true),
+    )
+    .append(ColoredDoc::colored_text(
+        &diagnostic.message,
+        color_spec(color, false),
+    ))
+    .append(ColoredDoc::line());
+
+    if let Some(location) = &diagnostic.location {
+        doc = doc.append(format_snippet(location, '^', color));
+    }
+
+    for related in &diagnostic.related {
+        doc = doc
+            .append(ColoredDoc::colored_text(
+                format!("  note: {}", related.message),
+                color_spec(Color::Blue, true),
+            ))
+            .append(ColoredDoc::line());
+        if let Some(location) = &related.location {
+            doc = doc.append(format_snippet(location, '-', Color::Blue));
+        }
+    }
+
+    if let Some(suggestion) = &diagnostic.suggestion {
+        doc = doc
+            .append(ColoredDoc::line())
+            .append(ColoredDoc::colored_text(
+                "  Suggestion:",
+                color_spec(Color::Cyan, true),
+            ))
+            .append(ColoredDoc::line())
+            .append(ColoredDoc::colored_text(
+                format!("  {}", suggestion.lines().collect::<Vec<_>>().join("\n  ")),
+                dimmed_spec(),
+            ))
+            .append(ColoredDoc::line());
+    }
+
+    doc.append(ColoredDoc::line())
+}
+
+/// Format a validation error as a ColoredDoc, listing every diagnostic grouped by table
 pub fn format_error(error: &ValidationError) -> ColoredDoc {
-    heading("VALIDATION ERROR", Color::Red)
+    let mut doc = heading("VALIDATION ERROR", Color::Red).append(ColoredDoc::line());
+
+    // Model-level diagnostics first, then one group per table in order of first appearance
+    let mut groups: Vec<(Option<&str>, Vec<&Diagnostic>)> = vec![(None, Vec::new())];
+    for diagnostic in &error.diagnostics {
+        let table = diagnostic.table.as_deref();
+        match groups.iter_mut().find(|(name, _)| *name == table) {
+            Some((_, members)) => members.push(diagnostic),
+            None => groups.push((table, vec![diagnostic])),
+        }
+    }
+
+    for (table, members) in groups {
+        if members.is_empty() {
+            continue;
+        }
+        if let Some(name) = table {
+            doc = doc.append(subheading(format!("TABLE {}", name), Color::Yellow));
+        }
+        for diagnostic in members {
+            doc = doc.append(format_diagnostic(diagnostic));
+        }
+    }
+
+    doc = doc
+        .append(ColoredDoc::text(format!(
+            "Found {} and {}.",
+            pluralize(error.errors().count(), "error"),
+            pluralize(error.warnings().count(), "warning")
+        )))
         .append(ColoredDoc::line())
         .append(ColoredDoc::colored_text(
-            format!("* {}", error.message),
-            color_spec(Color::Red, true),
+            "For more information about a diagnostic, run `ssvv explain <CODE>`.",
+            dimmed_spec(),
         ))
         .append(ColoredDoc::line())
-        .append(ColoredDoc::line())
-        .append(if error.is_yaml_error {
-            ColoredDoc::concat(vec![
-                ColoredDoc::colored_text("TIP:", color_spec(Color::Yellow, true)),
-                ColoredDoc::line(),
-                ColoredDoc::text("  Check the YAML syntax at the indicated line and column."),
-                ColoredDoc::line(),
-                ColoredDoc::text("  Common issues include:"),
-                ColoredDoc::line(),
-                ColoredDoc::text("    * Incorrect indentation (use spaces, not tabs)"),
-                ColoredDoc::line(),
-                ColoredDoc::text("    * Missing colons after keys"),
-                ColoredDoc::line(),
-                ColoredDoc::text("    * Unquoted strings containing special characters"),
-                ColoredDoc::line(),
-                ColoredDoc::text("    * Missing required fields"),
-                ColoredDoc::line(),
-                ColoredDoc::line(),
-            ])
-        } else {
-            ColoredDoc::text("")
-        })
-        .append(separator("=", Color::Red))
+        .append(ColoredDoc::line());
+
+    doc.append(if error.is_yaml_error() || error.is_structure_error() {
+        ColoredDoc::concat(vec![
+            ColoredDoc::colored_text("TIP:", color_spec(Color::Yellow, true)),
+            ColoredDoc::line(),
+            ColoredDoc::text("  Check the YAML syntax at the indicated line and column."),
+            ColoredDoc::line(),
+            ColoredDoc::text("  Common issues include:"),
+            ColoredDoc::line(),
+            ColoredDoc::text("    * Incorrect indentation (use spaces, not tabs)"),
+            ColoredDoc::line(),
+            ColoredDoc::text("    * Missing colons after keys"),
+            ColoredDoc::line(),
+            ColoredDoc::text("    * Unquoted strings containing special characters"),
+            ColoredDoc::line(),
+            ColoredDoc::text("    * Missing required fields"),
+            ColoredDoc::line(),
+            ColoredDoc::line(),
+        ])
+    } else {
+        ColoredDoc::text("")
+    })
+    .append(separator("=", Color::Red))
 }
 
 /// Format warnings as a ColoredDoc
-pub fn format_warnings(warnings: &[ValidationWarning]) -> ColoredDoc {
+pub fn format_warnings(warnings: &[Diagnostic]) -> ColoredDoc {
     if warnings.is_empty() {
         return ColoredDoc::text("");
     }
@@ -343,36 +1872,286 @@ pub fn format_warnings(warnings: &[ValidationWarning]) -> ColoredDoc {
     let mut doc = heading("WARNINGS", Color::Yellow).append(ColoredDoc::line());
 
     for warning in warnings {
+        doc = doc.append(format_diagnostic(warning));
+    }
+
+    doc.append(separator("-", Color::Yellow))
+        .append(ColoredDoc::line())
+}
+
+/// Format diagnostics as GitHub Actions workflow commands, which show up as annotations on pull
+/// requests. `file` is used for diagnostics that have no source location.
+pub fn format_github_annotations(file: &str, diagnostics: &[Diagnostic]) -> String {
+    // https://docs.github.com/en/actions/reference/workflow-commands-for-github-actions
+    let escape_data = |text: &str| {
+        text.replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A")
+    };
+    let escape_property = |text: &str| escape_data(text).replace(':', "%3A").replace(',', "%2C");
+
+    let mut output = String::new();
+    for diagnostic in diagnostics {
+        let mut properties = Vec::new();
+        match &diagnostic.location {
+            Some(location) => {
+                properties.push(format!("file={}", escape_property(&location.file)));
+                properties.push(format!("line={}", location.line));
+                properties.push(format!("col={}", location.column));
+                properties.push(format!("endColumn={}", location.end_column()));
+            }
+            None => properties.push(format!("file={}", escape_property(file))),
+        }
+        properties.push(format!(
+            "title={}",
+            escape_property(&format!(
+                "{} {}",
+                diagnostic.rule.code, diagnostic.rule.name
+            ))
+        ));
+
+        let mut message = diagnostic.message.clone();
+        if let Some(suggestion) = &diagnostic.suggestion {
+            message.push_str("\n\n");
+            message.push_str(suggestion);
+        }
+
+        output.push_str(&format!(
+            "::{} {}::{}\n",
+            diagnostic.severity.label(),
+            properties.join(","),
+            escape_data(&message)
+        ));
+    }
+    output
+}
+
+/// Format a table of per-file results, with totals, for runs that validate several files
+pub fn format_file_summary(reports: &[FileReport]) -> ColoredDoc {
+    let mut file_col = Column::new("File");
+    let mut status_col = Column::new("Status");
+    let mut errors_col = Column::new_aligned("Errors", Alignment::Right);
+    let mut warnings_col = Column::new_aligned("Warnings", Alignment::Right);
+
+    for report in reports {
+        let status = if report.valid {
+            Cell::colored("ok", color_spec(Color::Green, true))
+        } else {
+            Cell::colored("FAILED", color_spec(Color::Red, true))
+        };
+
+        file_col = file_col.add_cell(Cell::text(&report.file));
+        status_col = status_col.add_cell(status);
+        errors_col = errors_col.add_cell(Cell::text(report.errors.to_string()));
+        warnings_col = warnings_col.add_cell(Cell::text(report.warnings.to_string()));
+    }
+
+    let failed = reports.iter().filter(|r| !r.valid).count();
+    let errors: usize = reports.iter().map(|r| r.errors).sum();
+    let warnings: usize = reports.iter().map(|r| r.warnings).sum();
+    let totals_color = if failed > 0 { Color::Red } else { Color::Green };
+
+    subheading(format!("FILES ({})", reports.len()), Color::Blue)
+        .append(
+            TableRenderer::new()
+                .add_column(file_col)
+                .add_column(status_col)
+                .add_column(errors_col)
+                .add_column(warnings_col)
+                .render(),
+        )
+        .append(ColoredDoc::line())
+        .append(ColoredDoc::colored_text(
+            format!(
+                "{} passed, {} failed, with {} and {}.",
+                reports.len() - failed,
+                failed,
+                pluralize(errors, "error"),
+                pluralize(warnings, "warning")
+            ),
+            color_spec(totals_color, true),
+        ))
+        .append(ColoredDoc::line())
+}
+
+/// Format the diagnostics that appeared or were resolved since the previous run, as shown by
+/// `ssvv --watch`
+pub fn format_diagnostic_changes(changes: &DiagnosticChanges) -> ColoredDoc {
+    let mut doc = subheading("CHANGES SINCE LAST RUN", Color::Blue);
+
+    if changes.is_empty() {
+        return doc
+            .append(ColoredDoc::colored_text(
+                "No diagnostics changed.",
+                dimmed_spec(),
+            ))
+            .append(ColoredDoc::line());
+    }
+
+    let describe = |key: &DiagnosticKey| {
+        let location = if key.path.is_empty() {
+            key.file.clone()
+        } else {
+            format!("{} {}", key.file, key.path)
+        };
+        format!(
+            "{}[{}]: {} ({})",
+            key.severity.label(),
+            key.code,
+            key.message,
+            location
+        )
+    };
+
+    for key in &changes.appeared {
         doc = doc
             .append(ColoredDoc::colored_text(
-                "* ",
-                color_spec(Color::Yellow, true),
+                format!("+ {}", describe(key)),
+                color_spec(Color::Red, false),
             ))
+            .append(ColoredDoc::line());
+    }
+    for key in &changes.resolved {
+        doc = doc
             .append(ColoredDoc::colored_text(
-                &warning.message,
-                color_spec(Color::Yellow, false),
+                format!("- {}", describe(key)),
+                color_spec(Color::Green, false),
             ))
             .append(ColoredDoc::line());
+    }
 
-        if let Some(suggestion) = &warning.suggestion {
-            doc = doc
-                .append(ColoredDoc::line())
-                .append(ColoredDoc::colored_text(
-                    "  Suggestion:",
-                    color_spec(Color::Cyan, true),
-                ))
-                .append(ColoredDoc::line())
-                .append(ColoredDoc::colored_text(
-                    format!("  {}", suggestion.lines().collect::<Vec<_>>().join("\n  ")),
-                    dimmed_spec(),
-                ))
-                .append(ColoredDoc::line());
+    doc.append(ColoredDoc::colored_text(
+        format!(
+            "{} appeared, {} resolved.",
+            changes.appeared.len(),
+            changes.resolved.len()
+        ),
+        color_spec(Color::Blue, true),
+    ))
+    .append(ColoredDoc::line())
+}
+
+/// Format the long-form explanation of a rule, as shown by `ssvv explain`
+pub fn format_explanation(rule: &Rule) -> ColoredDoc {
+    let severity_color = match rule.severity {
+        Severity::Error => Color::Red,
+        Severity::Warning => Color::Yellow,
+    };
+
+    heading(format!("{}: {}", rule.code, rule.name), Color::Blue)
+        .append(ColoredDoc::line())
+        .append(ColoredDoc::colored_text(
+            "Default severity:",
+            color_spec(Color::Green, true),
+        ))
+        .append(ColoredDoc::text(" "))
+        .append(ColoredDoc::colored_text(
+            rule.severity.label(),
+            color_spec(severity_color, true),
+        ))
+        .append(ColoredDoc::line())
+        .append(ColoredDoc::colored_text(
+            "Summary:",
+            color_spec(Color::Green, true),
+        ))
+        .append(ColoredDoc::text(format!(" {}", rule.summary)))
+        .append(ColoredDoc::line())
+        .append(ColoredDoc::line())
+        .append(ColoredDoc::text(rule.explanation))
+        .append(ColoredDoc::line())
+        .append(ColoredDoc::line())
+        .append(separator("=", Color::Blue))
+}
+
+/// Format the dependency tree of `item`: the columns and metrics its expr refers to, and what
+/// those refer to in turn. A dependency that leads back to an item above it is marked as a cycle.
+pub fn format_dependency_tree(graph: &DependencyGraph, item: usize) -> ColoredDoc {
+    let mut lines = vec![dependency_line(graph, item, false)];
+    dependency_branches(graph, item, "", &mut vec![item], &mut lines);
+
+    subheading(
+        format!("DEPENDENCIES OF {}", graph.items()[item].qualified_name()),
+        Color::Blue,
+    )
+    .append(ColoredDoc::concat(lines))
+}
+
+/// One item in a dependency tree: its name, what it is and its expr
+fn dependency_line(graph: &DependencyGraph, item: usize, is_cycle: bool) -> ColoredDoc {
+    let item = &graph.items()[item];
+    let color = match item.kind {
+        ExprKind::Metric => Color::Cyan,
+        _ => Color::Green,
+    };
+    let expr: Vec<&str> = item.expr.split_whitespace().collect();
+
+    let mut line = ColoredDoc::colored_text(item.qualified_name(), color_spec(color, true)).append(
+        ColoredDoc::colored_text(
+            format!("  {}: {}", item.kind.label(), expr.join(" ")),
+            dimmed_spec(),
+        ),
+    );
+    if is_cycle {
+        line = line.append(ColoredDoc::colored_text(
+            "  (cycle)",
+            color_spec(Color::Red, true),
+        ));
+    }
+    line.append(ColoredDoc::line())
+}
+
+/// The branches below `item`, each line starting with `prefix`. `ancestors` are the items from the
+/// root of the tree down to `item`.
+fn dependency_branches(
+    graph: &DependencyGraph,
+    item: usize,
+    prefix: &str,
+    ancestors: &mut Vec<usize>,
+    lines: &mut Vec<ColoredDoc>,
+) {
+    let dependencies = graph.dependencies(item);
+    for (i, &dependency) in dependencies.iter().enumerate() {
+        let is_last = i + 1 == dependencies.len();
+        let is_cycle = ancestors.contains(&dependency);
+        let branch = if is_last { "└── " } else { "├── " };
+
+        lines.push(ColoredDoc::colored_text(
+            format!("{}{}", prefix, branch),
+            dimmed_spec(),
+        ));
+        lines.push(dependency_line(graph, dependency, is_cycle));
+        if !is_cycle {
+            let indent = if is_last { "    " } else { "│   " };
+            ancestors.push(dependency);
+            let prefix = format!("{}{}", prefix, indent);
+            dependency_branches(graph, dependency, &prefix, ancestors, lines);
+            ancestors.pop();
         }
-        doc = doc.append(ColoredDoc::line());
+    }
+}
+
+/// Format a table of every known rule
+pub fn format_rule_list() -> ColoredDoc {
+    let mut code_col = Column::new("Code");
+    let mut name_col = Column::new("Name");
+    let mut severity_col = Column::new("Severity");
+    let mut summary_col = Column::new("Summary");
+
+    for rule in RULES {
+        code_col = code_col.add_cell(Cell::text(rule.code));
+        name_col = name_col.add_cell(Cell::text(rule.name));
+        severity_col = severity_col.add_cell(Cell::text(rule.severity.label()));
+        summary_col = summary_col.add_cell(Cell::text(rule.summary));
     }
 
-    doc.append(separator("-", Color::Yellow))
-        .append(ColoredDoc::line())
+    subheading(format!("RULES ({})", RULES.len()), Color::Yellow).append(
+        TableRenderer::new()
+            .add_column(code_col)
+            .add_column(name_col)
+            .add_column(severity_col)
+            .add_column(summary_col)
+            .render(),
+    )
 }
 
 /// Count columns with descriptions across all tables
@@ -595,6 +2374,14 @@ fn count_table_aliased_columns(table: &Table) -> (usize, usize) {
 
 /// Format a successful validation result as a ColoredDoc
 pub fn format_success(model: &SemanticModel) -> ColoredDoc {
+    format_success_with_thresholds(model, &CoverageThresholds::default())
+}
+
+/// Like [`format_success`], colouring the data quality figures by the given thresholds
+pub fn format_success_with_thresholds(
+    model: &SemanticModel,
+    thresholds: &CoverageThresholds,
+) -> ColoredDoc {
     let mut doc = heading("SEMANTIC MODEL VALIDATION SUMMARY", Color::Blue)
         .append(ColoredDoc::line())
         .append(ColoredDoc::colored_text(
@@ -610,7 +2397,10 @@ pub fn format_success(model: &SemanticModel) -> ColoredDoc {
         .append(ColoredDoc::text(format!(" {}", model.description)))
         .append(ColoredDoc::line())
         .append(ColoredDoc::line())
-        .append(subheading(&format!("TABLES ({})", model.tables.len()), Color::Yellow));
+        .append(subheading(
+            format!("TABLES ({})", model.tables.len()),
+            Color::Yellow,
+        ));
 
     // Build table for tables section - column by column
     let mut name_col = Column::new("Name");
@@ -626,7 +2416,7 @@ pub fn format_success(model: &SemanticModel) -> ColoredDoc {
     for table_item in &model.tables {
         let (described_count, total_count) = count_table_described_columns(table_item);
         let (aliased_count, _) = count_table_aliased_columns(table_item);
-        
+
         let described_pct = if total_count > 0 {
             (described_count as f64 / total_count as f64) * 100.0
         } else {
@@ -670,7 +2460,10 @@ pub fn format_success(model: &SemanticModel) -> ColoredDoc {
         .append(ColoredDoc::line());
 
     // Relationships section
-    doc = doc.append(subheading(&format!("RELATIONSHIPS ({})", model.relationships.len()), Color::Yellow));
+    doc = doc.append(subheading(
+        format!("RELATIONSHIPS ({})", model.relationships.len()),
+        Color::Yellow,
+    ));
 
     if model.relationships.is_empty() {
         doc = doc
@@ -696,10 +2489,10 @@ pub fn format_success(model: &SemanticModel) -> ColoredDoc {
                 .join(", ");
 
             name_col = name_col.add_cell(Cell::text(&rel.name));
-            join_col = join_col.add_cell(Cell::text(&rel.join_type));
+            join_col = join_col.add_cell(Cell::text(rel.join_type.as_str()));
             left_col = left_col.add_cell(Cell::text(&rel.left_table));
             right_col = right_col.add_cell(Cell::text(&rel.right_table));
-            type_col = type_col.add_cell(Cell::text(&rel.relationship_type));
+            type_col = type_col.add_cell(Cell::text(rel.relationship_type.as_str()));
             columns_col = columns_col.add_cell(Cell::text(columns_str));
         }
 
@@ -716,7 +2509,10 @@ pub fn format_success(model: &SemanticModel) -> ColoredDoc {
     doc = doc.append(ColoredDoc::line());
 
     // Verified Queries section
-    doc = doc.append(subheading(&format!("VERIFIED QUERIES ({})", model.verified_queries.len()), Color::Yellow));
+    doc = doc.append(subheading(
+        format!("VERIFIED QUERIES ({})", model.verified_queries.len()),
+        Color::Yellow,
+    ));
 
     if model.verified_queries.is_empty() {
         doc = doc
@@ -869,28 +2665,27 @@ pub fn format_success(model: &SemanticModel) -> ColoredDoc {
     doc = doc.append(subheading("DATA QUALITY METRICS", Color::Yellow));
 
     // Determine color based on percentage
-    let described_color = if described_pct >= 80.0 {
-        Color::Green
-    } else if described_pct >= 50.0 {
-        Color::Yellow
-    } else {
-        Color::Red
-    };
-
-    let aliased_color = if aliased_pct >= 60.0 {
-        Color::Green
-    } else if aliased_pct >= 30.0 {
-        Color::Yellow
-    } else {
-        Color::Red
+    let threshold_color = |pct: f64, threshold: &Threshold| {
+        if pct >= threshold.good {
+            Color::Green
+        } else if pct >= threshold.fair {
+            Color::Yellow
+        } else {
+            Color::Red
+        }
     };
+    let described_color = threshold_color(described_pct, &thresholds.described);
+    let aliased_color = threshold_color(aliased_pct, &thresholds.aliased);
 
     doc = doc
         .append(ColoredDoc::colored_text(
             "  Described Columns:",
             color_spec(Color::Cyan, true),
         ))
-        .append(ColoredDoc::text(format!(" {} / {} ", described_count, total_columns)))
+        .append(ColoredDoc::text(format!(
+            " {} / {} ",
+            described_count, total_columns
+        )))
         .append(ColoredDoc::colored_text(
             format!("({:.1}%)", described_pct),
             color_spec(described_color, true),
@@ -900,7 +2695,10 @@ pub fn format_success(model: &SemanticModel) -> ColoredDoc {
             "  Aliased Columns:",
             color_spec(Color::Cyan, true),
         ))
-        .append(ColoredDoc::text(format!(" {} / {} ", aliased_count, total_columns)))
+        .append(ColoredDoc::text(format!(
+            " {} / {} ",
+            aliased_count, total_columns
+        )))
         .append(ColoredDoc::colored_text(
             format!("({:.1}%)", aliased_pct),
             color_spec(aliased_color, true),
@@ -911,13 +2709,18 @@ pub fn format_success(model: &SemanticModel) -> ColoredDoc {
             "  TIP:",
             color_spec(Color::Magenta, true),
         ))
-        .append(ColoredDoc::text(" Descriptions and synonyms help LLMs understand your data model better."))
+        .append(ColoredDoc::text(
+            " Descriptions and synonyms help LLMs understand your data model better.",
+        ))
         .append(ColoredDoc::line())
         .append(ColoredDoc::colored_text(
             "       ",
             color_spec(Color::Magenta, true),
         ))
-        .append(ColoredDoc::text("Aim for 80%+ described columns and 60%+ aliased columns for optimal results."))
+        .append(ColoredDoc::text(format!(
+            "Aim for {}%+ described columns and {}%+ aliased columns for optimal results.",
+            thresholds.described.good, thresholds.aliased.good
+        )))
         .append(ColoredDoc::line())
         .append(ColoredDoc::line());
 
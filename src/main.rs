@@ -1,8 +1,24 @@
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use notify_debouncer_full::notify::{EventKind, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, DebouncedEvent};
 use snowflake_semantic_view_validator::{
-    format_error, format_success, format_warnings, validate_file, ColoredDoc,
+    collect_model_files, color_spec, did_you_mean, dimmed_spec, find_rule, format_dependency_tree,
+    format_diagnostic_changes, format_error, format_explanation, format_file_summary,
+    format_github_annotations, format_rule_list, format_success_with_thresholds, format_warnings,
+    parse_file, subheading, validate_files, validate_reader_with_options, Baseline, ColoredDoc,
+    Config, ConfigError, CoverageThresholds, DependencyGraph, DiagnosticChanges, DiagnosticKey,
+    ExprKind, FileReport, Outcome, Report, ValidationError, ValidationOptions, ValidationResult,
+    WarningPolicy, CONFIG_FILE_NAME,
 };
-use termcolor::{ColorChoice, StandardStream};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+use termcolor::{Color, ColorChoice, StandardStream};
+
+/// How long to wait for a burst of file system events (e.g. an editor's save) to settle
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
 
 /// Snowflake Semantic View Validator (ssvv)
 ///
@@ -10,9 +26,81 @@ use termcolor::{ColorChoice, StandardStream};
 #[derive(Parser, Debug)]
 #[command(name = "ssvv")]
 #[command(about = "Snowflake Semantic View Validator", long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
-    /// Path to the semantic model YAML file to validate
-    file: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Semantic model files, directories (searched recursively) or glob patterns to validate.
+    /// Use `-` to read a model from stdin.
+    files: Vec<String>,
+
+    /// File name to show in diagnostics for a model read from stdin
+    #[arg(long, value_name = "NAME")]
+    stdin_filename: Option<String>,
+
+    /// Don't report keys that aren't part of the semantic model spec
+    #[arg(long)]
+    allow_unknown_keys: bool,
+
+    /// Output format [default: human]
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Configuration file to use for every file, instead of the nearest ssvv.toml
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Fail if there are any warnings
+    #[arg(long)]
+    deny_warnings: bool,
+
+    /// Fail if there are more than this many warnings across all files
+    #[arg(long, value_name = "N")]
+    max_warnings: Option<usize>,
+
+    /// Only report findings that aren't recorded in this baseline file
+    #[arg(long, value_name = "PATH")]
+    baseline: Option<PathBuf>,
+
+    /// Record every current finding in a baseline file, instead of reporting them
+    #[arg(long, value_name = "PATH", conflicts_with = "baseline")]
+    write_baseline: Option<PathBuf>,
+
+    /// Revalidate whenever a model file changes, until interrupted
+    #[arg(long)]
+    watch: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// Colored, human-readable summary
+    Human,
+    /// Versioned JSON document, for scripts and CI
+    Json,
+    /// SARIF 2.1.0 log, for GitHub code scanning
+    Sarif,
+    /// JUnit XML, with a testcase per rule, for CI test reports
+    Junit,
+    /// GitHub Actions workflow commands, for pull request annotations
+    Github,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Explain a diagnostic code (e.g. SSVV001), or list every code if none is given
+    Explain {
+        /// Diagnostic code or rule name
+        code: Option<String>,
+    },
+    /// Show the columns and metrics a metric is computed from
+    Deps {
+        /// Metric name, optionally qualified with its table (e.g. ORDERS.TOTAL_REVENUE)
+        metric: String,
+        /// Semantic model files, directories or glob patterns to look in [default: the configured
+        /// include patterns]
+        paths: Vec<String>,
+    },
 }
 
 fn format_help() -> ColoredDoc {
@@ -25,9 +113,57 @@ fn format_help() -> ColoredDoc {
         ColoredDoc::line(),
         ColoredDoc::text("USAGE:"),
         ColoredDoc::line(),
-        ColoredDoc::text("  ssvv <file.yaml>    Validate a semantic model file"),
+        ColoredDoc::text("  ssvv <file.yaml>       Validate a semantic model file"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  ssvv <paths>...        Validate files, directories and glob patterns"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  ssvv -                 Validate a semantic model read from stdin"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  ssvv --watch <paths>   Revalidate every time a model file is saved"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  ssvv explain <CODE>    Explain a diagnostic code, e.g. SSVV001"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  ssvv explain           List every diagnostic code"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  ssvv deps <METRIC> [paths]...  Show what a metric is computed from"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  ssvv --help            Show this help message"),
+        ColoredDoc::line(),
         ColoredDoc::line(),
-        ColoredDoc::text("  ssvv --help         Show this help message"),
+        ColoredDoc::text("OPTIONS:"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  --allow-unknown-keys   Don't report keys that aren't in the specification"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  --format <FORMAT>      Output format: human (default), json, sarif, junit or github"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  --stdin-filename <NAME>  File name to show for a model read from stdin"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  --config <PATH>        Use this configuration file instead of the nearest ssvv.toml"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  --deny-warnings        Fail if there are any warnings"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  --max-warnings <N>     Fail if there are more than N warnings"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  --baseline <PATH>      Only report findings that aren't in the baseline"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  --write-baseline <PATH>  Record the current findings as a baseline"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  --watch                Revalidate on every change, showing which diagnostics changed"),
+        ColoredDoc::line(),
+        ColoredDoc::line(),
+        ColoredDoc::text("EXIT CODES:"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  0  Every file is valid"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  1  A model violates the specification"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  2  Invalid command-line arguments or configuration"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  3  A file is not valid YAML"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  4  A file could not be read, or no files were found"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  5  There are more warnings than --deny-warnings or --max-warnings allow"),
         ColoredDoc::line(),
         ColoredDoc::line(),
         ColoredDoc::text("DESCRIPTION:"),
@@ -46,23 +182,133 @@ fn format_help() -> ColoredDoc {
         ColoredDoc::text("  and helpful advice on how to fix the issues."),
         ColoredDoc::line(),
         ColoredDoc::line(),
+        ColoredDoc::text("  Settings are read from the nearest ssvv.toml in the file's directory or a parent."),
+        ColoredDoc::line(),
+        ColoredDoc::line(),
         ColoredDoc::text("EXAMPLES:"),
         ColoredDoc::line(),
         ColoredDoc::text("  ssvv speedrun.yaml"),
         ColoredDoc::line(),
         ColoredDoc::text("  ssvv my-semantic-model.yml"),
         ColoredDoc::line(),
+        ColoredDoc::text("  ssvv models/ 'other/**/*.yaml'"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  ssvv explain SSVV006"),
+        ColoredDoc::line(),
+        ColoredDoc::text("  ssvv deps ORDERS.TOTAL_REVENUE models/"),
+        ColoredDoc::line(),
     ])
 }
 
-fn main() {
-    let cli = Cli::parse();
+fn explain(code: Option<String>) {
+    let mut stdout = StandardStream::stdout(ColorChoice::Auto);
 
-    match cli.file {
-        Some(path) => match validate_file(&path) {
-            Ok(result) => {
-                let mut stdout = StandardStream::stdout(ColorChoice::Auto);
+    match code {
+        Some(code) => match find_rule(&code) {
+            Some(rule) => format_explanation(rule)
+                .render_colored(&mut stdout)
+                .unwrap(),
+            None => {
+                let doc = ColoredDoc::text(format!("Unknown diagnostic code '{}'", code))
+                    .append(ColoredDoc::line())
+                    .append(ColoredDoc::line())
+                    .append(format_rule_list());
+                let mut stderr = StandardStream::stderr(ColorChoice::Auto);
+                doc.render_colored(&mut stderr).unwrap();
+                std::process::exit(2);
+            }
+        },
+        None => format_rule_list().render_colored(&mut stdout).unwrap(),
+    }
+}
+
+/// Print the dependency tree of every metric called `metric` in the models at `inputs`
+fn deps(metric: &str, inputs: &[String]) {
+    let mut stdout = StandardStream::stdout(ColorChoice::Auto);
+    let mut stderr = StandardStream::stderr(ColorChoice::Auto);
+    let file_set = collect_model_files(inputs);
+    for unmatched in &file_set.unmatched {
+        eprintln!("No semantic model files found for '{}'", unmatched);
+    }
+    if file_set.files.is_empty() {
+        std::process::exit(Outcome::UnreadableFile.exit_code());
+    }
+    let mut found = false;
+    let mut known = Vec::new();
+
+    for path in &file_set.files {
+        let model = match parse_file(path) {
+            Ok(model) => model,
+            Err(e) => {
+                format_error(&e).render_colored(&mut stderr).unwrap();
+                continue;
+            }
+        };
+        let graph = DependencyGraph::new(&model);
+        let matches = graph.find_metrics(metric);
+        if matches.is_empty() {
+            // Suggest names in the same form as the one given
+            let names = graph
+                .items()
+                .iter()
+                .filter(|item| item.kind == ExprKind::Metric)
+                .map(|item| {
+                    if metric.contains('.') {
+                        item.qualified_name()
+                    } else {
+                        item.name.clone()
+                    }
+                });
+            for name in names {
+                if !known.contains(&name) {
+                    known.push(name);
+                }
+            }
+            continue;
+        }
+
+        if file_set.files.len() > 1 {
+            subheading(path.display().to_string(), Color::Yellow)
+                .render_colored(&mut stdout)
+                .unwrap();
+        }
+        for item in matches {
+            format_dependency_tree(&graph, item)
+                .append(ColoredDoc::line())
+                .render_colored(&mut stdout)
+                .unwrap();
+            found = true;
+        }
+    }
 
+    if !found {
+        let mut message = format!("No metric named '{}' was found", metric);
+        if let Some(suggestion) = did_you_mean(metric, "metrics", known.iter().map(String::as_str))
+        {
+            message = format!("{}. {}", message, suggestion);
+        }
+        ColoredDoc::colored_text(message, color_spec(Color::Red, true))
+            .append(ColoredDoc::line())
+            .render_colored(&mut stderr)
+            .unwrap();
+        std::process::exit(2);
+    }
+}
+
+/// Print the human-readable results. A single file gets the full model summary; several files
+/// get their problems followed by a summary table.
+fn print_human(validated: &Validated) {
+    let mut stdout = StandardStream::stdout(ColorChoice::Auto);
+    let mut stderr = StandardStream::stderr(ColorChoice::Auto);
+    let Validated {
+        names,
+        results,
+        coverage,
+    } = validated;
+
+    if let ([result], [coverage]) = (results.as_slice(), coverage.as_slice()) {
+        match result {
+            Ok(result) => {
                 // Show warnings first if any
                 if !result.warnings.is_empty() {
                     let warnings_doc = format_warnings(&result.warnings);
@@ -70,20 +316,497 @@ fn main() {
                 }
 
                 // Then show the success summary
-                let doc = format_success(&result.model);
+                let doc = format_success_with_thresholds(&result.model, coverage);
                 doc.render_colored(&mut stdout).unwrap();
             }
             Err(e) => {
-                let doc = format_error(&e);
-                let mut stderr = StandardStream::stderr(ColorChoice::Auto);
+                let doc = format_error(e);
                 doc.render_colored(&mut stderr).unwrap();
-                std::process::exit(1);
             }
-        },
-        None => {
-            let help = format_help();
-            let mut stdout = StandardStream::stdout(ColorChoice::Auto);
-            help.render_colored(&mut stdout).unwrap();
+        }
+        return;
+    }
+
+    for (name, result) in names.iter().zip(results) {
+        let file_heading = subheading(format!("FILE {}", name), Color::Blue);
+        match result {
+            Ok(result) if !result.warnings.is_empty() => file_heading
+                .append(format_warnings(&result.warnings))
+                .render_colored(&mut stdout)
+                .unwrap(),
+            Ok(_) => {}
+            Err(e) => file_heading
+                .append(format_error(e))
+                .append(ColoredDoc::line())
+                .render_colored(&mut stderr)
+                .unwrap(),
         }
     }
+
+    let reports: Vec<FileReport> = names
+        .iter()
+        .zip(results)
+        .map(|(name, result)| FileReport::new(name, result))
+        .collect();
+    format_file_summary(&reports)
+        .render_colored(&mut stdout)
+        .unwrap();
+}
+
+/// The results of validating every input
+struct Validated {
+    /// Display name of each file
+    names: Vec<String>,
+    results: Vec<Result<ValidationResult, ValidationError>>,
+    /// Coverage thresholds configured for each file
+    coverage: Vec<CoverageThresholds>,
+}
+
+/// Command-line settings that apply on top of any configuration file
+#[derive(Clone, Default)]
+struct Overrides {
+    /// Set by `--config`, and used for every file instead of searching
+    config: Option<PathBuf>,
+    allow_unknown_keys: bool,
+    /// Loaded from `--baseline`
+    baseline: Option<Arc<Baseline>>,
+}
+
+/// Finds the configuration that applies to each file, loading every ssvv.toml only once
+struct Configs {
+    overrides: Overrides,
+    /// Loaded configurations by path. `None` holds the defaults, for files without one.
+    loaded: HashMap<Option<PathBuf>, Config>,
+}
+
+impl Configs {
+    fn new(overrides: Overrides) -> Self {
+        let mut loaded = HashMap::new();
+        loaded.insert(None, Config::default());
+        Configs { overrides, loaded }
+    }
+
+    /// The path of the configuration that applies to files in `dir`, loading it if needed
+    fn locate(&mut self, dir: &Path) -> Result<Option<PathBuf>, ConfigError> {
+        let path = match &self.overrides.config {
+            Some(path) => Some(path.clone()),
+            None => Config::find(dir),
+        };
+        if let Some(file) = &path {
+            if !self.loaded.contains_key(&path) {
+                let config = Config::load(file)?;
+                self.loaded.insert(path.clone(), config);
+            }
+        }
+        Ok(path)
+    }
+
+    /// The configuration that applies to files in `dir`
+    fn for_dir(&mut self, dir: &Path) -> Result<&Config, ConfigError> {
+        let path = self.locate(dir)?;
+        Ok(&self.loaded[&path])
+    }
+
+    fn options(&self, path: &Option<PathBuf>) -> ValidationOptions {
+        let mut options = self.loaded[path].validation_options();
+        options.allow_unknown_keys |= self.overrides.allow_unknown_keys;
+        options.baseline = self.overrides.baseline.clone();
+        options
+    }
+}
+
+/// The directory whose configuration applies to `file`
+fn config_dir(file: &Path) -> &Path {
+    match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// Validate every file selected by the command-line inputs, reading `-` from stdin, with the
+/// configuration that applies to each. Returns `None` if no files were found.
+fn validate_inputs(
+    inputs: &[String],
+    stdin_name: &str,
+    configs: &mut Configs,
+) -> Result<Option<Validated>, ConfigError> {
+    let file_set = collect_model_files(inputs);
+    for unmatched in &file_set.unmatched {
+        eprintln!("No semantic model files found for '{}'", unmatched);
+    }
+
+    // Files named on the command line are validated even if the configuration excludes them
+    let mut files = Vec::new();
+    let mut config_paths = Vec::new();
+    for file in &file_set.files {
+        let is_stdin = file.as_os_str() == "-";
+        let dir = if is_stdin {
+            Path::new(".")
+        } else {
+            config_dir(file)
+        };
+        let config_path = configs.locate(dir)?;
+        let named = inputs.iter().any(|input| Path::new(input) == file);
+        if !is_stdin && !named && configs.loaded[&config_path].is_excluded(file) {
+            continue;
+        }
+        files.push(file.clone());
+        config_paths.push(config_path);
+    }
+    if files.is_empty() {
+        if !file_set.files.is_empty() {
+            eprintln!("Every semantic model file found is excluded by the configuration");
+        }
+        return Ok(None);
+    }
+
+    let names = files
+        .iter()
+        .map(|path| {
+            if path.as_os_str() == "-" {
+                stdin_name.to_string()
+            } else {
+                path.display().to_string()
+            }
+        })
+        .collect();
+
+    // Validate the files sharing a configuration together, then put the results back in order
+    let mut results: Vec<Option<Result<ValidationResult, ValidationError>>> =
+        files.iter().map(|_| None).collect();
+    let mut groups: Vec<(&Option<PathBuf>, Vec<usize>)> = Vec::new();
+    for (index, config_path) in config_paths.iter().enumerate() {
+        match groups.iter_mut().find(|(path, _)| *path == config_path) {
+            Some((_, indices)) => indices.push(index),
+            None => groups.push((config_path, vec![index])),
+        }
+    }
+    for (config_path, indices) in groups {
+        let options = configs.options(config_path);
+        let (stdin, indices): (Vec<usize>, Vec<usize>) = indices
+            .into_iter()
+            .partition(|&index| files[index].as_os_str() == "-");
+
+        let group_files: Vec<PathBuf> = indices.iter().map(|&i| files[i].clone()).collect();
+        for (index, result) in indices
+            .into_iter()
+            .zip(validate_files(&group_files, &options))
+        {
+            results[index] = Some(result);
+        }
+        if let Some(&index) = stdin.first() {
+            let result = validate_reader_with_options(io::stdin().lock(), stdin_name, &options);
+            results[index] = Some(result);
+        }
+    }
+
+    Ok(Some(Validated {
+        names,
+        results: results.into_iter().flatten().collect(),
+        coverage: config_paths
+            .iter()
+            .map(|path| configs.loaded[path].coverage)
+            .collect(),
+    }))
+}
+
+/// The directory to watch for an input, and whether to watch it recursively. Files are watched
+/// through their directory, because many editors save by replacing the file.
+fn watch_root(input: &str) -> (PathBuf, RecursiveMode) {
+    let path = Path::new(input);
+    if path.is_dir() {
+        return (path.to_path_buf(), RecursiveMode::Recursive);
+    }
+
+    // For a glob, watch everything under the part of the pattern before the first wildcard
+    let is_glob = input.contains(['*', '?', '[']);
+    let literal_prefix: PathBuf = path
+        .components()
+        .take_while(|component| {
+            !component
+                .as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '['])
+        })
+        .collect();
+    let dir = if is_glob {
+        literal_prefix
+    } else {
+        path.parent().map(Path::to_path_buf).unwrap_or_default()
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        dir
+    };
+    let mode = if is_glob {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    (dir, mode)
+}
+
+/// Whether an event could affect the result: a change (not just a read, which validating causes
+/// itself) to a YAML file, a configuration file or one of the files given by name
+fn is_relevant_change(event: &DebouncedEvent, inputs: &[String]) -> bool {
+    !matches!(event.kind, EventKind::Access(_))
+        && event.paths.iter().any(|path| is_model_path(path, inputs))
+}
+
+fn is_model_path(path: &Path, inputs: &[String]) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == CONFIG_FILE_NAME)
+        || matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yaml" | "yml")
+        )
+        || inputs.iter().any(|input| {
+            Path::new(input).file_name().is_some()
+                && Path::new(input).file_name() == path.file_name()
+        })
+}
+
+/// Validate the inputs, then again after every change to them, until interrupted
+fn watch(inputs: &[String], overrides: Overrides) -> ! {
+    let (tx, rx) = mpsc::channel::<DebounceEventResult>();
+    let mut debouncer = new_debouncer(WATCH_DEBOUNCE, None, tx).unwrap_or_else(|e| {
+        eprintln!("Failed to start watching: {}", e);
+        std::process::exit(Outcome::UnreadableFile.exit_code());
+    });
+    for input in inputs {
+        let (dir, mode) = watch_root(input);
+        if let Err(e) = debouncer.watch(&dir, mode) {
+            eprintln!("Failed to watch '{}': {}", dir.display(), e);
+            std::process::exit(Outcome::UnreadableFile.exit_code());
+        }
+    }
+
+    let mut previous: Option<Vec<DiagnosticKey>> = None;
+    loop {
+        // Clear the screen and move the cursor to the top left
+        print!("\x1B[2J\x1B[1;1H");
+        let _ = io::stdout().flush();
+
+        let mut stdout = StandardStream::stdout(ColorChoice::Auto);
+        // Configuration is reloaded every time, so that changes to it apply straight away
+        let mut configs = Configs::new(overrides.clone());
+        match validate_inputs(inputs, "<stdin>", &mut configs) {
+            Ok(Some(validated)) => {
+                print_human(&validated);
+
+                let current = DiagnosticKey::collect(&validated.names, &validated.results);
+                if let Some(previous) = &previous {
+                    format_diagnostic_changes(&DiagnosticChanges::between(previous, &current))
+                        .render_colored(&mut stdout)
+                        .unwrap();
+                }
+                previous = Some(current);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("{}", e),
+        }
+
+        ColoredDoc::line()
+            .append(ColoredDoc::colored_text(
+                "Watching for changes. Press Ctrl+C to stop.",
+                dimmed_spec(),
+            ))
+            .append(ColoredDoc::line())
+            .render_colored(&mut stdout)
+            .unwrap();
+
+        // Wait for a change to a model file
+        loop {
+            match rx.recv() {
+                Ok(Ok(events)) => {
+                    if events.iter().any(|event| is_relevant_change(event, inputs)) {
+                        break;
+                    }
+                }
+                Ok(Err(errors)) => {
+                    for e in errors {
+                        eprintln!("Watch error: {}", e);
+                    }
+                }
+                Err(_) => std::process::exit(Outcome::UnreadableFile.exit_code()),
+            }
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    if let Some(Command::Explain { code }) = &cli.command {
+        explain(code.clone());
+        return;
+    }
+
+    let baseline = cli.baseline.as_deref().map(|path| {
+        Baseline::load(path).map(Arc::new).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        })
+    });
+    let mut configs = Configs::new(Overrides {
+        config: cli.config.clone(),
+        allow_unknown_keys: cli.allow_unknown_keys,
+        baseline,
+    });
+    // Settings for the run as a whole come from the configuration for the current directory
+    let project = match configs.for_dir(Path::new(".")) {
+        Ok(config) => config.clone(),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
+    let files = match &cli.command {
+        Some(Command::Deps { paths, .. }) => paths,
+        _ => &cli.files,
+    };
+    let inputs: Vec<String> = if files.is_empty() {
+        // Keep file names short when the configuration is in the current directory
+        let root = project.root();
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let root = root.strip_prefix(&cwd).unwrap_or(&root);
+        project
+            .include
+            .iter()
+            .map(|pattern| root.join(pattern).display().to_string())
+            .collect()
+    } else {
+        files.clone()
+    };
+
+    if let Some(Command::Deps { metric, .. }) = &cli.command {
+        deps(metric, &inputs);
+        return;
+    }
+
+    if inputs.is_empty() {
+        let help = format_help();
+        let mut stdout = StandardStream::stdout(ColorChoice::Auto);
+        help.render_colored(&mut stdout).unwrap();
+        return;
+    }
+
+    let format = match (cli.format, &project.format) {
+        (Some(format), _) => format,
+        (None, Some(name)) => OutputFormat::from_str(name, true).unwrap_or_else(|_| {
+            let allowed: Vec<String> = OutputFormat::value_variants()
+                .iter()
+                .filter_map(|format| format.to_possible_value())
+                .map(|value| value.get_name().to_string())
+                .collect();
+            eprintln!(
+                "{}",
+                ConfigError {
+                    path: project.path.clone().unwrap_or_default(),
+                    message: format!(
+                        "unknown format '{}'. Allowed values: {}",
+                        name,
+                        allowed.join(", ")
+                    ),
+                }
+            );
+            std::process::exit(2);
+        }),
+        (None, None) => OutputFormat::Human,
+    };
+
+    if cli.watch {
+        if inputs.iter().any(|input| input == "-") {
+            eprintln!("--watch can't be combined with reading from stdin");
+            std::process::exit(2);
+        }
+        if format != OutputFormat::Human {
+            eprintln!("--watch only supports the human output format");
+            std::process::exit(2);
+        }
+        watch(&inputs, configs.overrides);
+    }
+
+    let stdin_name = cli.stdin_filename.as_deref().unwrap_or("<stdin>");
+    let validated = match validate_inputs(&inputs, stdin_name, &mut configs) {
+        Ok(Some(validated)) => validated,
+        Ok(None) => std::process::exit(Outcome::UnreadableFile.exit_code()),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    let Validated { names, results, .. } = &validated;
+
+    if let Some(path) = &cli.write_baseline {
+        let baseline = Baseline::new(path, names, results);
+        if let Err(e) = baseline.save(path) {
+            eprintln!("{}", e);
+            std::process::exit(Outcome::UnreadableFile.exit_code());
+        }
+        println!(
+            "Wrote {} to {}",
+            match baseline.len() {
+                1 => "1 finding".to_string(),
+                n => format!("{} findings", n),
+            },
+            path.display()
+        );
+
+        // Only problems that can't be recorded still fail the run
+        let outcome = Outcome::of(results, &WarningPolicy::default());
+        let unreadable_model = results
+            .iter()
+            .any(|result| result.as_ref().is_err_and(|e| e.is_structure_error()));
+        if outcome >= Outcome::YamlSyntaxError || unreadable_model {
+            std::process::exit(outcome.exit_code());
+        }
+        return;
+    }
+
+    match format {
+        OutputFormat::Human => print_human(&validated),
+        OutputFormat::Github => {
+            for (name, result) in names.iter().zip(results) {
+                let diagnostics = match result {
+                    Ok(result) => &result.warnings,
+                    Err(e) => &e.diagnostics,
+                };
+                print!("{}", format_github_annotations(name, diagnostics));
+            }
+        }
+        format => {
+            let report = Report::new(
+                names
+                    .iter()
+                    .zip(results)
+                    .map(|(name, result)| FileReport::new(name, result))
+                    .collect(),
+            );
+            match format {
+                OutputFormat::Sarif => println!("{}", report.to_sarif()),
+                OutputFormat::Junit => print!("{}", report.to_junit()),
+                _ => println!("{}", report.to_json()),
+            }
+        }
+    }
+
+    let policy = WarningPolicy {
+        deny_warnings: cli.deny_warnings || project.warnings.deny_warnings,
+        max_warnings: cli.max_warnings.or(project.warnings.max_warnings),
+    };
+    let warnings = results
+        .iter()
+        .map(|result| match result {
+            Ok(result) => result.warnings.len(),
+            Err(e) => e.diagnostics.iter().filter(|d| !d.is_error()).count(),
+        })
+        .sum();
+    if let Some(violation) = policy.violation(warnings) {
+        eprintln!("{}", violation);
+    }
+
+    std::process::exit(Outcome::of(results, &policy).exit_code());
 }
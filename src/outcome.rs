@@ -0,0 +1,95 @@
+use crate::rules;
+use crate::{pluralize, ValidationError, ValidationResult};
+
+/// When warnings should fail a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WarningPolicy {
+    /// Fail if there are any warnings
+    pub deny_warnings: bool,
+    /// Fail if there are more warnings than this, across all files
+    pub max_warnings: Option<usize>,
+}
+
+impl WarningPolicy {
+    /// Why `warnings` warnings fail the run, or `None` if they're allowed
+    pub fn violation(&self, warnings: usize) -> Option<String> {
+        if self.deny_warnings && warnings > 0 {
+            return Some(format!(
+                "Found {} and warnings are denied (--deny-warnings)",
+                pluralize(warnings, "warning")
+            ));
+        }
+        match self.max_warnings {
+            Some(max) if warnings > max => Some(format!(
+                "Found {}, more than the maximum of {} (--max-warnings)",
+                pluralize(warnings, "warning"),
+                max
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// The overall result of a run, which decides the process exit code. When several files fail
+/// differently, the outcome is the most serious one, so that problems with the environment (like
+/// a missing file) aren't mistaken for problems in a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Outcome {
+    /// Every file is valid and the warnings are within the policy
+    Success,
+    /// The models are valid, but have more warnings than the policy allows
+    LintFailure,
+    /// A model breaks the semantic model specification, or doesn't have its structure
+    SpecViolation,
+    /// A file isn't valid YAML
+    YamlSyntaxError,
+    /// A file couldn't be read, or no files were found
+    UnreadableFile,
+}
+
+impl Outcome {
+    /// Decide the outcome of validating a set of files
+    pub fn of(
+        results: &[Result<ValidationResult, ValidationError>],
+        policy: &WarningPolicy,
+    ) -> Outcome {
+        let mut outcome = Outcome::Success;
+        let mut warnings = 0;
+
+        for result in results {
+            let diagnostics = match result {
+                Ok(result) => &result.warnings,
+                Err(error) => &error.diagnostics,
+            };
+            warnings += diagnostics.iter().filter(|d| !d.is_error()).count();
+
+            for diagnostic in diagnostics.iter().filter(|d| d.is_error()) {
+                let file_outcome = if diagnostic.rule.code == rules::UNREADABLE_FILE.code {
+                    Outcome::UnreadableFile
+                } else if diagnostic.rule.code == rules::INVALID_YAML.code {
+                    Outcome::YamlSyntaxError
+                } else {
+                    Outcome::SpecViolation
+                };
+                outcome = outcome.max(file_outcome);
+            }
+        }
+
+        if outcome == Outcome::Success && policy.violation(warnings).is_some() {
+            Outcome::LintFailure
+        } else {
+            outcome
+        }
+    }
+
+    /// The process exit code. 2 is left for command-line usage errors, which clap reports.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Outcome::Success => 0,
+            Outcome::SpecViolation => 1,
+            Outcome::YamlSyntaxError => 3,
+            Outcome::UnreadableFile => 4,
+            Outcome::LintFailure => 5,
+        }
+    }
+}
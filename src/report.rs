@@ -0,0 +1,227 @@
+use crate::diagnostic::{Diagnostic, Location};
+use crate::{
+    count_aliased_columns, count_described_columns, count_table_aliased_columns,
+    count_table_described_columns, SemanticModel, ValidationError, ValidationResult,
+};
+use serde::Serialize;
+
+/// Version of the JSON report format. Bumped whenever a field is removed or changes meaning;
+/// new fields may be added without a bump.
+pub const REPORT_VERSION: u32 = 1;
+
+/// The machine-readable result of validating one or more files
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub version: u32,
+    pub results: Vec<FileReport>,
+}
+
+impl Report {
+    pub fn new(results: Vec<FileReport>) -> Self {
+        Report {
+            version: REPORT_VERSION,
+            results,
+        }
+    }
+
+    /// Render the report as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("reports only contain JSON-compatible values")
+    }
+}
+
+/// The result of validating a single file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub file: String,
+    /// Whether the file has no errors (it may still have warnings)
+    pub valid: bool,
+    pub errors: usize,
+    pub warnings: usize,
+    pub diagnostics: Vec<DiagnosticReport>,
+    /// Summary of the model, if it could be parsed and is valid
+    pub model: Option<ModelSummary>,
+}
+
+impl FileReport {
+    pub fn new(
+        file: impl Into<String>,
+        result: &Result<ValidationResult, ValidationError>,
+    ) -> Self {
+        let (diagnostics, model) = match result {
+            Ok(result) => (&result.warnings, Some(ModelSummary::new(&result.model))),
+            Err(error) => (&error.diagnostics, None),
+        };
+        let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+
+        FileReport {
+            file: file.into(),
+            valid: errors == 0,
+            errors,
+            warnings: diagnostics.len() - errors,
+            diagnostics: diagnostics.iter().map(DiagnosticReport::new).collect(),
+            model,
+        }
+    }
+}
+
+/// A diagnostic, flattened for serialization
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticReport {
+    pub code: &'static str,
+    pub rule: &'static str,
+    pub severity: &'static str,
+    pub message: String,
+    /// Path of the node within the document, e.g. `tables[0].dimensions[1].name`
+    pub path: Option<String>,
+    pub table: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Column just past the end of the offending text
+    pub end_column: Option<usize>,
+    pub suggestion: Option<String>,
+    pub related: Vec<RelatedReport>,
+}
+
+impl DiagnosticReport {
+    fn new(diagnostic: &Diagnostic) -> Self {
+        DiagnosticReport {
+            code: diagnostic.rule.code,
+            rule: diagnostic.rule.name,
+            severity: diagnostic.severity.label(),
+            message: diagnostic.message.clone(),
+            path: display_path(&diagnostic.path.to_string()),
+            table: diagnostic.table.clone(),
+            line: diagnostic.location.as_ref().map(|l| l.line),
+            column: diagnostic.location.as_ref().map(|l| l.column),
+            end_column: diagnostic.location.as_ref().map(Location::end_column),
+            suggestion: diagnostic.suggestion.clone(),
+            related: diagnostic
+                .related
+                .iter()
+                .map(|related| RelatedReport {
+                    message: related.message.clone(),
+                    path: display_path(&related.path.to_string()),
+                    line: related.location.as_ref().map(|l| l.line),
+                    column: related.location.as_ref().map(|l| l.column),
+                    end_column: related.location.as_ref().map(Location::end_column),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Another location involved in a diagnostic
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedReport {
+    pub message: String,
+    pub path: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Column just past the end of the related text
+    pub end_column: Option<usize>,
+}
+
+/// The figures shown in the human-readable validation summary
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelSummary {
+    pub name: String,
+    pub description: String,
+    pub tables: Vec<TableSummary>,
+    pub relationships: usize,
+    pub verified_queries: usize,
+    /// Number of model-level (derived) metrics
+    pub metrics: usize,
+    pub data_quality: DataQuality,
+}
+
+impl ModelSummary {
+    pub fn new(model: &SemanticModel) -> Self {
+        let (described, total) = count_described_columns(model);
+        let (aliased, _) = count_aliased_columns(model);
+
+        ModelSummary {
+            name: model.name.clone(),
+            description: model.description.clone(),
+            tables: model
+                .tables
+                .iter()
+                .map(|table| {
+                    let (described, total) = count_table_described_columns(table);
+                    let (aliased, _) = count_table_aliased_columns(table);
+                    TableSummary {
+                        name: table.name.clone(),
+                        location: format!(
+                            "{}.{}.{}",
+                            table.base_table.database,
+                            table.base_table.schema,
+                            table.base_table.table
+                        ),
+                        dimensions: table.dimensions.len(),
+                        time_dimensions: table.time_dimensions.len(),
+                        facts: table.facts.len(),
+                        metrics: table.metrics.len(),
+                        filters: table.filters.len(),
+                        data_quality: DataQuality::new(described, aliased, total),
+                    }
+                })
+                .collect(),
+            relationships: model.relationships.len(),
+            verified_queries: model.verified_queries.len(),
+            metrics: model.metrics.len(),
+            data_quality: DataQuality::new(described, aliased, total),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableSummary {
+    pub name: String,
+    /// Fully qualified base table, `DATABASE.SCHEMA.TABLE`
+    pub location: String,
+    pub dimensions: usize,
+    pub time_dimensions: usize,
+    pub facts: usize,
+    pub metrics: usize,
+    pub filters: usize,
+    pub data_quality: DataQuality,
+}
+
+/// How many columns have descriptions and synonyms
+#[derive(Debug, Clone, Serialize)]
+pub struct DataQuality {
+    pub total_columns: usize,
+    pub described_columns: usize,
+    pub aliased_columns: usize,
+    pub described_percent: f64,
+    pub aliased_percent: f64,
+}
+
+impl DataQuality {
+    fn new(described: usize, aliased: usize, total: usize) -> Self {
+        let percent = |count: usize| {
+            if total > 0 {
+                (count as f64 / total as f64) * 100.0
+            } else {
+                0.0
+            }
+        };
+
+        DataQuality {
+            total_columns: total,
+            described_columns: described,
+            aliased_columns: aliased,
+            described_percent: percent(described),
+            aliased_percent: percent(aliased),
+        }
+    }
+}
+
+/// The root path renders as an empty string, which is clearer as `null` in JSON
+fn display_path(path: &str) -> Option<String> {
+    if path.is_empty() {
+        None
+    } else {
+        Some(path.to_string())
+    }
+}
@@ -0,0 +1,575 @@
+use crate::diagnostic::Severity;
+
+/// A validation check with a stable code, so it can be referenced, documented and suppressed
+/// independently of the wording of its messages
+#[derive(Debug, PartialEq, Eq)]
+pub struct Rule {
+    /// Stable identifier, e.g. `SSVV001`
+    pub code: &'static str,
+    /// Short kebab-case name, e.g. `empty-table`
+    pub name: &'static str,
+    /// Severity used unless overridden
+    pub severity: Severity,
+    /// One-line description
+    pub summary: &'static str,
+    /// Long-form explanation shown by `ssvv explain`
+    pub explanation: &'static str,
+}
+
+pub static EMPTY_TABLE: Rule = Rule {
+    code: "SSVV001",
+    name: "empty-table",
+    severity: Severity::Error,
+    summary: "A table defines no dimensions, time dimensions, facts or metrics",
+    explanation: "\
+Every logical table must expose at least one column to Cortex Analyst, through
+'dimensions', 'time_dimensions', 'facts' or 'metrics'. A table with none of these
+has nothing that can be queried.
+
+Add at least one column to the table:
+
+  - name: ORDERS
+    base_table: ...
+    dimensions:
+      - name: ORDER_ID
+        expr: ORDER_ID
+        data_type: VARCHAR",
+};
+
+pub static EMPTY_TABLE_NAME: Rule = Rule {
+    code: "SSVV002",
+    name: "empty-table-name",
+    severity: Severity::Error,
+    summary: "A table has an empty 'name'",
+    explanation: "\
+Each logical table needs a non-empty 'name'. The name is how relationships,
+metrics and Cortex Analyst itself refer to the table.
+
+  - name: ORDERS
+    base_table: ...",
+};
+
+pub static EMPTY_MODEL_NAME: Rule = Rule {
+    code: "SSVV003",
+    name: "empty-model-name",
+    severity: Severity::Error,
+    summary: "The semantic model has an empty 'name'",
+    explanation: "\
+The top-level 'name' field identifies the semantic model and must not be empty.
+
+  name: SALES_MODEL
+  description: ...",
+};
+
+pub static NO_TABLES: Rule = Rule {
+    code: "SSVV004",
+    name: "no-tables",
+    severity: Severity::Error,
+    summary: "The semantic model defines no tables",
+    explanation: "\
+A semantic model describes one or more logical tables. The top-level 'tables' list
+must contain at least one entry.",
+};
+
+pub static EMPTY_MODULE_INSTRUCTIONS: Rule = Rule {
+    code: "SSVV005",
+    name: "empty-module-instructions",
+    severity: Severity::Error,
+    summary: "'module_custom_instructions' defines no instructions",
+    explanation: "\
+When 'module_custom_instructions' is present it must define at least one of
+'question_categorization' or 'sql_generation'. Either add instructions or remove
+the block entirely.
+
+  module_custom_instructions:
+    sql_generation: |
+      Round all monetary values to 2 decimal places.",
+};
+
+pub static DEPRECATED_CUSTOM_INSTRUCTIONS: Rule = Rule {
+    code: "SSVV006",
+    name: "deprecated-custom-instructions",
+    severity: Severity::Warning,
+    summary: "The top-level 'custom_instructions' field is deprecated",
+    explanation: "\
+Snowflake has replaced the free-form 'custom_instructions' field with
+'module_custom_instructions', which separates instructions for question
+categorization from instructions for SQL generation.
+
+Existing instructions almost always belong under 'sql_generation':
+
+  module_custom_instructions:
+    sql_generation: |
+      <your existing custom_instructions>",
+};
+
+pub static INVALID_YAML: Rule = Rule {
+    code: "SSVV007",
+    name: "invalid-yaml",
+    severity: Severity::Error,
+    summary: "The file is not valid YAML",
+    explanation: "\
+The file could not be parsed as YAML, so there is no model to check.
+
+Common causes:
+  * Incorrect indentation (use spaces, not tabs)
+  * Missing colons after keys
+  * Unquoted strings containing special characters",
+};
+
+pub static UNREADABLE_FILE: Rule = Rule {
+    code: "SSVV008",
+    name: "unreadable-file",
+    severity: Severity::Error,
+    summary: "The file could not be read",
+    explanation: "\
+The file does not exist, is not readable by the current user, or is not valid
+UTF-8. Check the path and permissions.",
+};
+
+pub static UNKNOWN_RELATIONSHIP_TABLE: Rule = Rule {
+    code: "SSVV009",
+    name: "unknown-relationship-table",
+    severity: Severity::Error,
+    summary: "A relationship refers to a table that isn't defined in the model",
+    explanation: "\
+A relationship's 'left_table' and 'right_table' must each be the 'name' of a
+logical table in the same semantic model (not the physical base table). Names
+are compared case-insensitively, as Snowflake does for unquoted identifiers.
+
+  tables:
+    - name: RUNS
+      ...
+    - name: GAMES
+      ...
+  relationships:
+    - name: runs_to_games
+      left_table: RUNS
+      right_table: GAMES",
+};
+
+pub static UNKNOWN_RELATIONSHIP_COLUMN: Rule = Rule {
+    code: "SSVV010",
+    name: "unknown-relationship-column",
+    severity: Severity::Error,
+    summary: "A relationship joins on a column that its table doesn't define",
+    explanation: "\
+Each 'left_column' in 'relationship_columns' must match the 'name' (or 'expr') of a
+dimension, time dimension or fact on the relationship's 'left_table', and each
+'right_column' must do the same on its 'right_table'.
+
+  relationship_columns:
+    - left_column: GAME_ID    # a column of left_table
+      right_column: GAME_ID   # a column of right_table",
+};
+
+pub static MISSING_PRIMARY_KEY: Rule = Rule {
+    code: "SSVV011",
+    name: "missing-primary-key",
+    severity: Severity::Error,
+    summary: "A relationship joins to a table that has no primary key",
+    explanation: "\
+For 'many_to_one' and 'one_to_one' relationships, Snowflake requires the table on
+the right-hand side to declare a 'primary_key', and the relationship's right
+columns to be exactly that key. Without it Cortex Analyst can't be sure the join
+doesn't fan out rows.
+
+Declare the primary key on the right table:
+
+  - name: GAMES
+    base_table: ...
+    primary_key:
+      columns:
+        - GAME_ID",
+};
+
+pub static RELATIONSHIP_KEY_MISMATCH: Rule = Rule {
+    code: "SSVV012",
+    name: "relationship-key-mismatch",
+    severity: Severity::Error,
+    summary: "A relationship's right columns aren't the right table's primary key",
+    explanation: "\
+For 'many_to_one' and 'one_to_one' relationships, the 'right_column's must be
+exactly the columns of the right table's 'primary_key': every key column must be
+joined on, and nothing else. For a composite key, list one relationship column
+per key column:
+
+  primary_key:
+    columns:
+      - PRODUCT_ID
+      - WAREHOUSE_ID
+
+  relationship_columns:
+    - left_column: PRODUCT_ID
+      right_column: PRODUCT_ID
+    - left_column: WAREHOUSE_ID
+      right_column: WAREHOUSE_ID",
+};
+
+pub static UNKNOWN_KEY: Rule = Rule {
+    code: "SSVV013",
+    name: "unknown-key",
+    severity: Severity::Error,
+    summary: "A key isn't part of the semantic model spec",
+    explanation: "\
+The semantic model spec defines a fixed set of keys for each kind of entry. Any
+other key is ignored when the model is loaded, so a misspelling such as
+'time_dimension:' (instead of 'time_dimensions:') or 'synonym:' (instead of
+'synonyms:') silently discards everything underneath it.
+
+Fix the spelling of the key, or remove it. If you deliberately keep extra keys in
+your models, run ssvv with --allow-unknown-keys to skip this check.",
+};
+
+pub static DUPLICATE_NAME: Rule = Rule {
+    code: "SSVV014",
+    name: "duplicate-name",
+    severity: Severity::Error,
+    summary: "Two entries in the same scope share a name",
+    explanation: "\
+Names must be unique within the scope Snowflake resolves them in, compared
+case-insensitively:
+
+  * table names, relationship names and verified query names across the model
+  * dimension, time dimension, fact and metric names within a table, since they
+    are all referenced as <table>.<name>
+  * filter names within a table
+  * model-level metric names, which must also not clash with any table's metrics
+
+Rename one of the clashing entries, or remove it if it's a copy of the other.",
+};
+
+pub static UNKNOWN_DATA_TYPE: Rule = Rule {
+    code: "SSVV015",
+    name: "unknown-data-type",
+    severity: Severity::Error,
+    summary: "A 'data_type' isn't a Snowflake data type",
+    explanation: "\
+Each 'data_type' must name a Snowflake data type, or one of its aliases:
+
+  * NUMBER, DECIMAL, NUMERIC, INT, INTEGER, BIGINT, SMALLINT, TINYINT, BYTEINT
+  * FLOAT, DOUBLE, DOUBLE PRECISION, REAL
+  * VARCHAR, CHAR, STRING, TEXT, BINARY, VARBINARY
+  * BOOLEAN
+  * DATE, TIME, DATETIME, TIMESTAMP, TIMESTAMP_LTZ, TIMESTAMP_NTZ, TIMESTAMP_TZ
+  * VARIANT, OBJECT, ARRAY, GEOGRAPHY, GEOMETRY, VECTOR
+
+Type names are case-insensitive.",
+};
+
+pub static INVALID_TYPE_PARAMETERS: Rule = Rule {
+    code: "SSVV016",
+    name: "invalid-type-parameters",
+    severity: Severity::Error,
+    summary: "A 'data_type' has missing, malformed or out-of-range parameters",
+    explanation: "\
+Parameterised types must use the parameters Snowflake accepts:
+
+  * NUMBER(p, s): precision 1-38, scale 0 up to the precision
+  * VARCHAR(n): length 1-134217728
+  * BINARY(n): length 1-67108864
+  * TIME(p), TIMESTAMP_*(p): fractional-seconds precision 0-9
+  * VECTOR(INT|FLOAT, n): dimension 1-4096
+
+Types such as INT, FLOAT, BOOLEAN and DATE take no parameters.
+
+  data_type: NUMBER(38,2)",
+};
+
+pub static NON_TEMPORAL_TIME_DIMENSION: Rule = Rule {
+    code: "SSVV017",
+    name: "non-temporal-time-dimension",
+    severity: Severity::Error,
+    summary: "A time dimension's 'data_type' isn't a date or time type",
+    explanation: "\
+Cortex Analyst uses 'time_dimensions' for date filtering and time-based grouping,
+so their 'data_type' must be DATE, TIME, or a TIMESTAMP type (TIMESTAMP,
+TIMESTAMP_LTZ, TIMESTAMP_NTZ, TIMESTAMP_TZ). A time dimension declared as, say,
+VARCHAR leads to generated SQL that compares dates as strings.
+
+If the column really holds dates, declare its type:
+
+  time_dimensions:
+    - name: RUN_DATE
+      expr: RUN_DATE
+      data_type: DATE
+
+Otherwise, move it to 'dimensions'.",
+};
+
+pub static TEMPORAL_DIMENSION: Rule = Rule {
+    code: "SSVV018",
+    name: "temporal-dimension",
+    severity: Severity::Warning,
+    summary: "A regular dimension has a date or time 'data_type'",
+    explanation: "\
+Dimensions with a DATE, TIME or TIMESTAMP type belong in 'time_dimensions'. Cortex
+Analyst only applies date filtering and time-based grouping (by month, quarter and
+so on) to time dimensions, so questions like \"runs last month\" won't use a date
+column that is declared as a regular dimension.
+
+  time_dimensions:
+    - name: RUN_DATE
+      expr: RUN_DATE
+      data_type: DATE",
+};
+
+pub static INVALID_SUPPRESSION: Rule = Rule {
+    code: "SSVV019",
+    name: "invalid-suppression",
+    severity: Severity::Warning,
+    summary: "An 'ssvv-ignore' comment can't be applied",
+    explanation: "\
+Diagnostics can be suppressed with a comment naming the rules to ignore, by code or
+name. '# ssvv-ignore:' applies to the node on the next line and everything inside
+it; '# ssvv-ignore-file:' applies to the whole file:
+
+  # ssvv-ignore-file: deprecated-custom-instructions
+  tables:
+    # ssvv-ignore: SSVV018
+    - name: RUNS
+
+This is reported when a comment names a rule that doesn't exist, names no rules,
+isn't followed by a node, or names a rule that can't be suppressed: invalid-yaml,
+invalid-structure and unreadable-file (nothing can be checked without them) and the
+suppression rules themselves.",
+};
+
+pub static UNUSED_SUPPRESSION: Rule = Rule {
+    code: "SSVV020",
+    name: "unused-suppression",
+    severity: Severity::Warning,
+    summary: "An 'ssvv-ignore' comment doesn't suppress anything",
+    explanation: "\
+An 'ssvv-ignore' or 'ssvv-ignore-file' comment names a rule that reports nothing
+where the comment applies. Usually the problem it suppressed has been fixed, so the
+comment can be removed. Left in place, it would hide the problem if it came back.",
+};
+
+pub static INVALID_EXPRESSION: Rule = Rule {
+    code: "SSVV021",
+    name: "invalid-expression",
+    severity: Severity::Error,
+    summary: "An 'expr' is not a valid Snowflake SQL expression",
+    explanation: "\
+The 'expr' of every dimension, time dimension, fact, metric and filter is a SQL
+expression that Snowflake evaluates against the table. An expression that doesn't
+parse, such as one with an unbalanced parenthesis or a missing argument, fails when
+the semantic view is created or queried:
+
+  metrics:
+    - name: TOTAL_AMOUNT
+      expr: SUM(ORDER_AMOUNT      # missing ')'
+    - name: CUSTOMER_COUNT
+      expr: COUNT(DISTINCT)       # missing the column to count
+
+Each 'expr' must be a single expression, so a trailing comma or a second expression
+is reported too. The diagnostic points at the position in the expression where
+parsing failed.",
+};
+
+pub static UNKNOWN_REFERENCE: Rule = Rule {
+    code: "SSVV022",
+    name: "unknown-reference",
+    severity: Severity::Error,
+    summary: "A metric or filter 'expr' refers to a name the model doesn't define",
+    explanation: "\
+Metric and filter expressions are written in terms of the logical columns and
+metrics of the model:
+
+  - A table's metrics and filters refer to the table's own dimensions, time
+    dimensions, facts and metrics by name.
+  - Model-level metrics refer to the metrics of any table by name.
+  - 'TABLE.NAME' refers to a column or metric of another logical table.
+
+  metrics:
+    - name: TOTAL_REVENUE
+      expr: SUM(ORDER_AMNT)                   # no column ORDER_AMNT in this table
+  ...
+  metrics:
+    - name: REVENUE_PER_CUSTOMER
+      expr: TOTAL_REVENUE / COUNT(DISTINCT CUSTOMER.CUSTOMER_ID)   # no table CUSTOMER
+
+Dimension, time dimension and fact expressions aren't checked, since they refer to
+the physical columns of the base table. Date and time parts passed to functions
+(like the 'day' in 'DATEADD(day, 1, ORDER_DATE)') and expressions containing a
+subquery are skipped too.",
+};
+
+pub static METRIC_WITHOUT_AGGREGATE: Rule = Rule {
+    code: "SSVV023",
+    name: "metric-without-aggregate",
+    severity: Severity::Error,
+    summary: "A metric's 'expr' doesn't aggregate",
+    explanation: "\
+A metric combines the rows of each group into a single value, so its 'expr' must
+use an aggregate function such as SUM, COUNT, AVG, MIN or MAX, or be built from
+other metrics. An expression that is evaluated for each row belongs in a fact or
+dimension instead.
+
+  metrics:
+    - name: TOTAL_AMOUNT
+      expr: ORDER_AMOUNT            # reported
+    - name: TOTAL_AMOUNT
+      expr: SUM(ORDER_AMOUNT)       # aggregates
+    - name: AVERAGE_AMOUNT
+      expr: TOTAL_AMOUNT / ORDER_COUNT   # built from other metrics
+
+A function with an OVER clause is a window function, not an aggregate.",
+};
+
+pub static ROW_LEVEL_AGGREGATE: Rule = Rule {
+    code: "SSVV024",
+    name: "row-level-aggregate",
+    severity: Severity::Error,
+    summary: "A dimension, time dimension or fact 'expr' uses an aggregate or window function",
+    explanation: "\
+Dimensions, time dimensions and facts are evaluated for each row of the base
+table, and are grouped and aggregated by queries. Their expressions can't use
+aggregate functions (like SUM or COUNT) or window functions (anything with an
+OVER clause).
+
+  facts:
+    - name: TOTAL_AMOUNT
+      expr: SUM(ORDER_AMOUNT)       # reported: make it a metric
+
+Move aggregates to 'metrics'. Values that need a window function are best computed
+in the base table, or in a view used as the base table.",
+};
+
+pub static NESTED_AGGREGATE: Rule = Rule {
+    code: "SSVV025",
+    name: "nested-aggregate",
+    severity: Severity::Error,
+    summary: "A metric nests aggregates",
+    explanation: "\
+Snowflake doesn't allow an aggregate function inside another one, so a metric
+like 'SUM(COUNT(ORDER_ID))' fails. Aggregating another metric is the same mistake,
+since metrics are already aggregated:
+
+  metrics:
+    - name: ORDER_COUNT
+      expr: COUNT(ORDER_ID)
+    - name: TOTAL_ORDERS
+      expr: SUM(ORDER_COUNT)        # reported: ORDER_COUNT is already a COUNT
+
+Aggregate the column directly, or use the metric's value as it is. An aggregate
+inside a window function, as in 'SUM(SUM(AMOUNT)) OVER ()', is allowed.",
+};
+
+pub static METRIC_CYCLE: Rule = Rule {
+    code: "SSVV026",
+    name: "metric-cycle",
+    severity: Severity::Error,
+    summary: "Metrics depend on each other in a cycle",
+    explanation: "\
+A metric built from other metrics can't, directly or through them, be built from
+itself: there would be no way to compute any metric in the cycle.
+
+  metrics:
+    - name: AVERAGE_ORDER_VALUE
+      expr: TOTAL_REVENUE / ORDER_COUNT
+    - name: TOTAL_REVENUE
+      expr: AVERAGE_ORDER_VALUE * ORDER_COUNT   # reported: cycles back
+
+Each cycle is reported once, at the first of its metrics, with the others listed.
+'ssvv deps METRIC' prints what a metric depends on.",
+};
+
+pub static DISCONNECTED_METRIC_TABLES: Rule = Rule {
+    code: "SSVV027",
+    name: "disconnected-metric-tables",
+    severity: Severity::Error,
+    summary: "A metric combines tables that no relationships connect",
+    explanation: "\
+A metric that uses the columns or metrics of several tables, directly or through
+other metrics, can only be computed if relationships join those tables, either
+directly or through other tables.
+
+  tables:
+    - name: ORDERS
+      metrics:
+        - name: TOTAL_REVENUE
+          expr: SUM(ORDER_AMOUNT)
+    - name: WEB_VISITS
+      metrics:
+        - name: VISIT_COUNT
+          expr: COUNT(VISIT_ID)
+  metrics:
+    - name: REVENUE_PER_VISIT
+      expr: TOTAL_REVENUE / VISIT_COUNT   # reported without a relationship
+
+Add a relationship between the tables, or between tables that connect them. A
+metric that only uses a metric with this problem isn't reported again.",
+};
+
+pub static INVALID_VALUE: Rule = Rule {
+    code: "SSVV028",
+    name: "invalid-value",
+    severity: Severity::Error,
+    summary: "A field only allows certain values, and has another one",
+    explanation: "\
+Some fields only allow the values the specification lists:
+
+  * join_type (relationships): left_outer, inner
+  * relationship_type (relationships): many_to_one, one_to_one
+  * aggregation (facts): sum, avg, median, min, max, count, count_distinct
+  * access_modifier (facts and metrics): public_access, private_access
+
+Values are case-insensitive. A misspelled value comes with the closest allowed one.",
+};
+
+pub static INVALID_STRUCTURE: Rule = Rule {
+    code: "SSVV029",
+    name: "invalid-structure",
+    severity: Severity::Error,
+    summary: "The YAML doesn't have the structure of a semantic model",
+    explanation: "\
+The file is valid YAML, but it couldn't be read as a semantic model, so most
+other rules never ran.
+
+Common causes:
+  * Missing required fields such as 'name', 'base_table' or 'expr'
+  * A list where a single value is expected, or the other way around
+  * A mapping or list where text is expected",
+};
+
+/// Every rule, in code order
+pub static RULES: &[&Rule] = &[
+    &EMPTY_TABLE,
+    &EMPTY_TABLE_NAME,
+    &EMPTY_MODEL_NAME,
+    &NO_TABLES,
+    &EMPTY_MODULE_INSTRUCTIONS,
+    &DEPRECATED_CUSTOM_INSTRUCTIONS,
+    &INVALID_YAML,
+    &UNREADABLE_FILE,
+    &UNKNOWN_RELATIONSHIP_TABLE,
+    &UNKNOWN_RELATIONSHIP_COLUMN,
+    &MISSING_PRIMARY_KEY,
+    &RELATIONSHIP_KEY_MISMATCH,
+    &UNKNOWN_KEY,
+    &DUPLICATE_NAME,
+    &UNKNOWN_DATA_TYPE,
+    &INVALID_TYPE_PARAMETERS,
+    &NON_TEMPORAL_TIME_DIMENSION,
+    &TEMPORAL_DIMENSION,
+    &INVALID_SUPPRESSION,
+    &UNUSED_SUPPRESSION,
+    &INVALID_EXPRESSION,
+    &UNKNOWN_REFERENCE,
+    &METRIC_WITHOUT_AGGREGATE,
+    &ROW_LEVEL_AGGREGATE,
+    &NESTED_AGGREGATE,
+    &METRIC_CYCLE,
+    &DISCONNECTED_METRIC_TABLES,
+    &INVALID_VALUE,
+    &INVALID_STRUCTURE,
+];
+
+/// Look up a rule by its code or name, ignoring case
+pub fn find_rule(code_or_name: &str) -> Option<&'static Rule> {
+    RULES.iter().copied().find(|rule| {
+        rule.code.eq_ignore_ascii_case(code_or_name) || rule.name.eq_ignore_ascii_case(code_or_name)
+    })
+}
@@ -0,0 +1,109 @@
+use crate::report::{DiagnosticReport, Report};
+use crate::rules::RULES;
+use serde_json::{json, Value};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/krisajenkins/snowflake-semantic-view-validator";
+
+impl Report {
+    /// Render the report as a SARIF 2.1.0 log, e.g. for GitHub code scanning
+    pub fn to_sarif(&self) -> String {
+        let rules: Vec<Value> = RULES
+            .iter()
+            .map(|rule| {
+                json!({
+                    "id": rule.code,
+                    "name": rule.name,
+                    "shortDescription": { "text": rule.summary },
+                    "fullDescription": { "text": rule.explanation },
+                    "help": { "text": rule.explanation },
+                    "defaultConfiguration": { "level": rule.severity.label() },
+                })
+            })
+            .collect();
+
+        let results: Vec<Value> = self
+            .results
+            .iter()
+            .flat_map(|file| {
+                let uri = file.file.replace('\\', "/");
+                file.diagnostics
+                    .iter()
+                    .map(move |diagnostic| sarif_result(&uri, diagnostic))
+            })
+            .collect();
+
+        let log = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "ssvv",
+                        "informationUri": INFORMATION_URI,
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }],
+        });
+
+        serde_json::to_string_pretty(&log).expect("SARIF logs only contain JSON-compatible values")
+    }
+}
+
+fn sarif_result(uri: &str, diagnostic: &DiagnosticReport) -> Value {
+    let related: Vec<Value> = diagnostic
+        .related
+        .iter()
+        .enumerate()
+        .map(|(i, related)| {
+            let mut location =
+                physical_location(uri, related.line, related.column, related.end_column);
+            location["id"] = json!(i);
+            location["message"] = json!({ "text": related.message });
+            location
+        })
+        .collect();
+
+    let mut result = json!({
+        "ruleId": diagnostic.code,
+        "ruleIndex": RULES.iter().position(|rule| rule.code == diagnostic.code),
+        "level": diagnostic.severity,
+        "message": { "text": diagnostic.message },
+        "locations": [physical_location(
+            uri,
+            diagnostic.line,
+            diagnostic.column,
+            diagnostic.end_column,
+        )],
+        "relatedLocations": related,
+    });
+    // Results have no help of their own in SARIF, so the suggestion goes in the property bag
+    if let Some(suggestion) = &diagnostic.suggestion {
+        result["properties"] = json!({ "help": { "text": suggestion } });
+    }
+    result
+}
+
+/// A SARIF location, with a region only if the position is known
+fn physical_location(
+    uri: &str,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_column: Option<usize>,
+) -> Value {
+    let mut physical = json!({ "artifactLocation": { "uri": uri } });
+    if let Some(line) = line {
+        let mut region = json!({ "startLine": line });
+        if let Some(column) = column {
+            region["startColumn"] = json!(column);
+        }
+        if let Some(end_column) = end_column.filter(|end| Some(*end) > column) {
+            region["endColumn"] = json!(end_column);
+        }
+        physical["region"] = region;
+    }
+    json!({ "physicalLocation": physical })
+}
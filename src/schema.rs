@@ -0,0 +1,280 @@
+use crate::diagnostic::{Diagnostic, NodePath};
+use crate::rules;
+use crate::suggest;
+use crate::yaml_tree::{Node, NodeKind};
+use crate::{
+    BaseTable, CortexSearchService, Dimension, Fact, Filter, Metric, ModuleCustomInstructions,
+    PrimaryKey, Relationship, RelationshipColumn, SemanticModel, Table, TimeDimension,
+    VerifiedQuery,
+};
+use serde::de::{self, DeserializeOwned, Deserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use std::fmt;
+
+/// The keys allowed in one kind of mapping in the semantic model spec
+pub struct Schema {
+    /// What this mapping is, for messages (e.g. "dimension")
+    pub name: &'static str,
+    /// Every allowed key: the fields of the struct the mapping deserializes into
+    pub fields: fn() -> &'static [&'static str],
+    /// The keys whose values are checked further. Other keys hold scalars or lists of scalars.
+    pub children: &'static [(&'static str, Child)],
+}
+
+/// What a key's value is expected to contain
+pub enum Child {
+    Mapping(&'static Schema),
+    List(&'static Schema),
+}
+
+impl Schema {
+    fn is_key(&self, key: &str) -> bool {
+        (self.fields)().contains(&key)
+    }
+
+    fn child(&self, key: &str) -> Option<&Child> {
+        self.children
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, child)| child)
+    }
+
+    fn key_names(&self) -> impl Iterator<Item = &'static str> + Clone {
+        (self.fields)().iter().copied()
+    }
+}
+
+/// The field names of a struct, as its derived `Deserialize` impl expects them, so that the keys
+/// allowed here can't fall out of step with the model structs
+fn fields_of<T: DeserializeOwned>() -> &'static [&'static str] {
+    match T::deserialize(FieldRecorder) {
+        Err(Recorded::Fields(fields)) => fields,
+        _ => &[],
+    }
+}
+
+/// A deserializer that stops as soon as it's asked for a struct, with the struct's field names
+struct FieldRecorder;
+
+#[derive(Debug)]
+enum Recorded {
+    Fields(&'static [&'static str]),
+    Other,
+}
+
+impl fmt::Display for Recorded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not a struct")
+    }
+}
+
+impl std::error::Error for Recorded {}
+
+impl de::Error for Recorded {
+    fn custom<T: fmt::Display>(_: T) -> Self {
+        Recorded::Other
+    }
+}
+
+impl<'de> Deserializer<'de> for FieldRecorder {
+    type Error = Recorded;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Recorded> {
+        Err(Recorded::Other)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, Recorded> {
+        Err(Recorded::Fields(fields))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+pub static MODEL: Schema = Schema {
+    name: "semantic model",
+    fields: fields_of::<SemanticModel>,
+    children: &[
+        ("tables", Child::List(&TABLE)),
+        ("relationships", Child::List(&RELATIONSHIP)),
+        ("verified_queries", Child::List(&VERIFIED_QUERY)),
+        (
+            "module_custom_instructions",
+            Child::Mapping(&MODULE_CUSTOM_INSTRUCTIONS),
+        ),
+        ("metrics", Child::List(&METRIC)),
+    ],
+};
+
+static MODULE_CUSTOM_INSTRUCTIONS: Schema = Schema {
+    name: "module_custom_instructions",
+    fields: fields_of::<ModuleCustomInstructions>,
+    children: &[],
+};
+
+static TABLE: Schema = Schema {
+    name: "table",
+    fields: fields_of::<Table>,
+    children: &[
+        ("base_table", Child::Mapping(&BASE_TABLE)),
+        ("primary_key", Child::Mapping(&PRIMARY_KEY)),
+        ("dimensions", Child::List(&DIMENSION)),
+        ("time_dimensions", Child::List(&TIME_DIMENSION)),
+        ("facts", Child::List(&FACT)),
+        ("metrics", Child::List(&METRIC)),
+        ("filters", Child::List(&FILTER)),
+    ],
+};
+
+static BASE_TABLE: Schema = Schema {
+    name: "base_table",
+    fields: fields_of::<BaseTable>,
+    children: &[],
+};
+
+static PRIMARY_KEY: Schema = Schema {
+    name: "primary_key",
+    fields: fields_of::<PrimaryKey>,
+    children: &[],
+};
+
+static DIMENSION: Schema = Schema {
+    name: "dimension",
+    fields: fields_of::<Dimension>,
+    children: &[(
+        "cortex_search_service",
+        Child::Mapping(&CORTEX_SEARCH_SERVICE),
+    )],
+};
+
+static CORTEX_SEARCH_SERVICE: Schema = Schema {
+    name: "cortex_search_service",
+    fields: fields_of::<CortexSearchService>,
+    children: &[],
+};
+
+static TIME_DIMENSION: Schema = Schema {
+    name: "time dimension",
+    fields: fields_of::<TimeDimension>,
+    children: &[],
+};
+
+static FACT: Schema = Schema {
+    name: "fact",
+    fields: fields_of::<Fact>,
+    children: &[],
+};
+
+static METRIC: Schema = Schema {
+    name: "metric",
+    fields: fields_of::<Metric>,
+    children: &[],
+};
+
+static FILTER: Schema = Schema {
+    name: "filter",
+    fields: fields_of::<Filter>,
+    children: &[],
+};
+
+static RELATIONSHIP: Schema = Schema {
+    name: "relationship",
+    fields: fields_of::<Relationship>,
+    children: &[("relationship_columns", Child::List(&RELATIONSHIP_COLUMN))],
+};
+
+static RELATIONSHIP_COLUMN: Schema = Schema {
+    name: "relationship column",
+    fields: fields_of::<RelationshipColumn>,
+    children: &[],
+};
+
+static VERIFIED_QUERY: Schema = Schema {
+    name: "verified query",
+    fields: fields_of::<VerifiedQuery>,
+    children: &[],
+};
+
+/// Report every mapping key in the document that the semantic model spec doesn't define
+pub fn unknown_keys(root: &Node) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    check_mapping(
+        root,
+        root,
+        &MODEL,
+        &NodePath::root(),
+        None,
+        &mut diagnostics,
+    );
+    diagnostics
+}
+
+fn check_mapping(
+    root: &Node,
+    node: &Node,
+    schema: &'static Schema,
+    path: &NodePath,
+    table: Option<&str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let NodeKind::Mapping(entries) = &node.kind else {
+        return;
+    };
+
+    for (key, value) in entries {
+        let Some(key_name) = key.as_str() else {
+            continue;
+        };
+        let key_path = path.key(key_name);
+
+        if !schema.is_key(key_name) {
+            let location = if path.segments().is_empty() {
+                String::new()
+            } else {
+                format!(" at {}", root.named_path(path))
+            };
+            let mut diagnostic = Diagnostic::new(
+                &rules::UNKNOWN_KEY,
+                format!("Unknown key '{}' in {}{}", key_name, schema.name, location),
+            )
+            .at(key_path);
+            if let Some(table) = table {
+                diagnostic = diagnostic.in_table(table);
+            }
+            if let Some(suggestion) = suggest::did_you_mean(key_name, "keys", schema.key_names()) {
+                diagnostic = diagnostic.with_suggestion(suggestion);
+            }
+            diagnostics.push(diagnostic);
+            continue;
+        }
+
+        match schema.child(key_name) {
+            None => {}
+            Some(Child::Mapping(child)) => {
+                check_mapping(root, value, child, &key_path, table, diagnostics);
+            }
+            Some(Child::List(child)) => {
+                let NodeKind::Sequence(items) = &value.kind else {
+                    continue;
+                };
+                for (i, item) in items.iter().enumerate() {
+                    let item_table = if std::ptr::eq(*child, &TABLE) {
+                        item.get("name").and_then(Node::as_str)
+                    } else {
+                        table
+                    };
+                    let item_path = key_path.index(i);
+                    check_mapping(root, item, child, &item_path, item_table, diagnostics);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A field whose value must be one of a fixed set of strings in the semantic model spec.
+///
+/// Other values are kept as an `Unknown` variant rather than failing to parse, so that validation
+/// can report them alongside every other problem in the file.
+pub trait SpecEnum: Sized + Clone + PartialEq + 'static {
+    /// The YAML key the value appears under, for error messages
+    const FIELD: &'static str;
+    /// Every allowed value, as written in YAML
+    const VALUES: &'static [(&'static str, Self)];
+
+    /// A value the spec doesn't allow, as written
+    fn unknown(name: &str) -> Self;
+
+    /// The value as written, if the spec doesn't allow it
+    fn unknown_name(&self) -> Option<&str>;
+
+    /// The value as written in YAML
+    fn as_str(&self) -> &str {
+        if let Some(name) = self.unknown_name() {
+            return name;
+        }
+        Self::VALUES
+            .iter()
+            .find(|(_, value)| value == self)
+            .map(|(name, _)| *name)
+            .unwrap_or_default()
+    }
+
+    /// Look up a value, ignoring case
+    fn from_name(name: &str) -> Option<Self> {
+        Self::VALUES
+            .iter()
+            .find(|(candidate, _)| candidate.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.clone())
+    }
+
+    /// The allowed values, as written in YAML
+    fn names() -> impl Iterator<Item = &'static str> + Clone {
+        Self::VALUES.iter().map(|(name, _)| *name)
+    }
+}
+
+/// Deserialize a [`SpecEnum`], keeping values the spec doesn't allow as `Unknown`
+fn deserialize_spec_enum<'de, D: Deserializer<'de>, T: SpecEnum>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    deserializer.deserialize_str(SpecEnumVisitor(PhantomData))
+}
+
+struct SpecEnumVisitor<T>(PhantomData<T>);
+
+impl<T: SpecEnum> Visitor<'_> for SpecEnumVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a {} string", T::FIELD)
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<T, E> {
+        Ok(T::from_name(name).unwrap_or_else(|| T::unknown(name)))
+    }
+}
+
+macro_rules! spec_enum_impls {
+    ($type:ident) => {
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserialize_spec_enum(deserializer)
+            }
+        }
+
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl fmt::Display for $type {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.as_str())
+            }
+        }
+    };
+}
+
+/// The `unknown` and `unknown_name` methods of [`SpecEnum`], for an enum with an `Unknown(String)`
+/// variant
+macro_rules! unknown_variant {
+    () => {
+        fn unknown(name: &str) -> Self {
+            Self::Unknown(name.to_string())
+        }
+
+        fn unknown_name(&self) -> Option<&str> {
+            match self {
+                Self::Unknown(name) => Some(name),
+                _ => None,
+            }
+        }
+    };
+}
+
+/// How a relationship joins its left table to its right table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoinType {
+    LeftOuter,
+    Inner,
+    /// A value the spec doesn't allow, as written
+    Unknown(String),
+}
+
+impl SpecEnum for JoinType {
+    const FIELD: &'static str = "join_type";
+    const VALUES: &'static [(&'static str, Self)] = &[
+        ("left_outer", JoinType::LeftOuter),
+        ("inner", JoinType::Inner),
+    ];
+
+    unknown_variant!();
+}
+
+spec_enum_impls!(JoinType);
+
+/// How many rows of the right table each row of the left table matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelationshipType {
+    ManyToOne,
+    OneToOne,
+    /// A value the spec doesn't allow, as written
+    Unknown(String),
+}
+
+impl SpecEnum for RelationshipType {
+    const FIELD: &'static str = "relationship_type";
+    const VALUES: &'static [(&'static str, Self)] = &[
+        ("many_to_one", RelationshipType::ManyToOne),
+        ("one_to_one", RelationshipType::OneToOne),
+    ];
+
+    unknown_variant!();
+}
+
+spec_enum_impls!(RelationshipType);
+
+/// The default aggregation applied to a fact
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Aggregation {
+    Sum,
+    Avg,
+    Median,
+    Min,
+    Max,
+    Count,
+    CountDistinct,
+    /// A value the spec doesn't allow, as written
+    Unknown(String),
+}
+
+impl SpecEnum for Aggregation {
+    const FIELD: &'static str = "aggregation";
+    const VALUES: &'static [(&'static str, Self)] = &[
+        ("sum", Aggregation::Sum),
+        ("avg", Aggregation::Avg),
+        ("median", Aggregation::Median),
+        ("min", Aggregation::Min),
+        ("max", Aggregation::Max),
+        ("count", Aggregation::Count),
+        ("count_distinct", Aggregation::CountDistinct),
+    ];
+
+    unknown_variant!();
+}
+
+spec_enum_impls!(Aggregation);
+
+/// Whether a fact or metric can be queried directly, or only used by other metrics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessModifier {
+    PublicAccess,
+    PrivateAccess,
+    /// A value the spec doesn't allow, as written
+    Unknown(String),
+}
+
+impl SpecEnum for AccessModifier {
+    const FIELD: &'static str = "access_modifier";
+    const VALUES: &'static [(&'static str, Self)] = &[
+        ("public_access", AccessModifier::PublicAccess),
+        ("private_access", AccessModifier::PrivateAccess),
+    ];
+
+    unknown_variant!();
+}
+
+spec_enum_impls!(AccessModifier);
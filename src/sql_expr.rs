@@ -0,0 +1,356 @@
+use crate::yaml_tree::Span;
+use sqlparser::ast::{
+    Expr, Function, FunctionArg, FunctionArgExpr, FunctionArguments, Ident, Query, Spanned, Visit,
+    Visitor,
+};
+use sqlparser::dialect::SnowflakeDialect;
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::Token;
+use std::fmt;
+use std::ops::ControlFlow;
+
+/// Date and time parts that functions like DATEADD and DATEDIFF take as a bare word, e.g. the `day`
+/// in `DATEADD(day, -7, ORDER_DATE)`
+const DATE_PARTS: &str = "\
+    year years y yy yyy yyyy yr yrs quarter quarters q qtr qtrs month months mm mon mons \
+    week weeks w wk weekofyear woy wy weekiso week_iso weekofyeariso weekofyear_iso \
+    day days d dd dayofmonth dayofweek weekday dow dw dayofweekiso dayofweek_iso weekday_iso \
+    dow_iso dw_iso dayofyear yearday doy dy yearofweek yearofweekiso \
+    hour hours h hh hr hrs minute minutes m mi min mins second seconds s sec secs \
+    millisecond milliseconds ms msec microsecond microseconds us usec \
+    nanosecond nanoseconds ns nsec nanosec nsecond nanosecs nseconds \
+    epoch epoch_second epoch_seconds epoch_millisecond epoch_milliseconds \
+    epoch_microsecond epoch_microseconds epoch_nanosecond epoch_nanoseconds \
+    timezone_hour tzh timezone_minute tzm";
+
+/// Functions whose first argument is a date or time part
+const DATE_PART_FUNCTIONS: &str = "\
+    DATEADD DATEDIFF DATE_PART DATE_TRUNC TIMEADD TIMEDIFF TIMESTAMPADD TIMESTAMPDIFF";
+
+/// Snowflake's aggregate functions. Most of them also work as window functions, with OVER.
+const AGGREGATE_FUNCTIONS: &str = "\
+    ANY_VALUE APPROX_COUNT_DISTINCT APPROX_PERCENTILE APPROX_PERCENTILE_ACCUMULATE \
+    APPROX_PERCENTILE_COMBINE APPROX_TOP_K APPROX_TOP_K_ACCUMULATE APPROX_TOP_K_COMBINE \
+    APPROXIMATE_JACCARD_INDEX APPROXIMATE_SIMILARITY ARRAY_AGG ARRAY_UNION_AGG ARRAY_UNIQUE_AGG \
+    AVG BITAND_AGG BITMAP_CONSTRUCT_AGG BITMAP_OR_AGG BITOR_AGG BITXOR_AGG BOOLAND_AGG \
+    BOOLOR_AGG BOOLXOR_AGG CORR COUNT COUNT_IF COVAR_POP COVAR_SAMP GROUPING GROUPING_ID \
+    HASH_AGG HLL HLL_ACCUMULATE HLL_COMBINE KURTOSIS LISTAGG MAX MAX_BY MEDIAN MIN MIN_BY \
+    MINHASH MINHASH_COMBINE MODE OBJECT_AGG PERCENTILE_CONT PERCENTILE_DISC REGR_AVGX \
+    REGR_AVGY REGR_COUNT REGR_INTERCEPT REGR_R2 REGR_SLOPE REGR_SXX REGR_SXY REGR_SYY SKEW \
+    STDDEV STDDEV_POP STDDEV_SAMP SUM VAR_POP VAR_SAMP VARIANCE VARIANCE_POP VARIANCE_SAMP";
+
+/// Why an `expr` isn't a valid Snowflake SQL expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExprError {
+    pub message: String,
+    /// Where the problem is, as a 1-based line and column within the expression
+    pub span: Span,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// Parse `sql` as a single Snowflake SQL expression. Anything after the expression, such as a
+/// second expression separated by a comma, is an error.
+pub fn parse(sql: &str) -> Result<Expr, ExprError> {
+    let dialect = SnowflakeDialect {};
+    let mut parser = Parser::new(&dialect)
+        .try_with_sql(sql)
+        .map_err(|e| error(sql, e))?;
+    let expr = parser.parse_expr().map_err(|e| error(sql, e))?;
+
+    let next = parser.peek_token();
+    if next.token != Token::EOF && is_case_keyword(&expr) {
+        // When the body of a CASE doesn't parse, sqlparser falls back to reading the keyword as a
+        // column name, which hides the real problem. Parse it as a CASE again to find it.
+        let mut parser = Parser::new(&dialect)
+            .try_with_sql(sql)
+            .map_err(|e| error(sql, e))?;
+        parser.next_token();
+        parser.parse_case_expr().map_err(|e| error(sql, e))?;
+    }
+    if next.token != Token::EOF {
+        let start = next.span.start;
+        let end = next.span.end;
+        let length = if end.line == start.line {
+            end.column.saturating_sub(start.column) as usize
+        } else {
+            1
+        };
+        return Err(ExprError {
+            message: format!(
+                "Unexpected '{}' after the end of the expression",
+                next.token
+            ),
+            span: Span {
+                line: start.line as usize,
+                column: start.column as usize,
+                length: length.max(1),
+            },
+        });
+    }
+
+    Ok(expr)
+}
+
+/// Whether `expr` is the CASE keyword read as a column name
+fn is_case_keyword(expr: &Expr) -> bool {
+    matches!(expr, Expr::Identifier(ident)
+        if ident.quote_style.is_none() && ident.value.eq_ignore_ascii_case("CASE"))
+}
+
+/// Convert a sqlparser error, taking the position out of its message. Errors without one are
+/// about running out of input, so they point just past the end of the expression.
+fn error(sql: &str, error: ParserError) -> ExprError {
+    let message = match error {
+        ParserError::TokenizerError(message) | ParserError::ParserError(message) => message,
+        ParserError::RecursionLimitExceeded => "Expression is nested too deeply".to_string(),
+    };
+
+    let (message, position) = match message.rsplit_once(" at Line: ") {
+        Some((message, position)) => (message.to_string(), parse_position(position)),
+        None => (message, None),
+    };
+    let (line, column) = position.unwrap_or_else(|| end_of(sql));
+
+    ExprError {
+        message: message.replace("found: EOF", "found: end of expression"),
+        span: Span {
+            line,
+            column,
+            length: 1,
+        },
+    }
+}
+
+/// Parse the `1, Column: 5` that follows "at Line: " in sqlparser messages
+fn parse_position(position: &str) -> Option<(usize, usize)> {
+    let (line, column) = position.split_once(", Column: ")?;
+    let line = line.trim().parse().ok()?;
+    let column = column.trim().parse().ok()?;
+    (line > 0 && column > 0).then_some((line, column))
+}
+
+/// The position just after the last character of `sql`, ignoring trailing whitespace
+fn end_of(sql: &str) -> (usize, usize) {
+    let sql = sql.trim_end();
+    let line = sql.split('\n').count();
+    let column = sql.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+/// A column or metric named in an expression, e.g. `ORDER_AMOUNT` or `CUSTOMERS.CUSTOMER_ID`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    /// The parts of the name, e.g. the table and column of a qualified name
+    pub parts: Vec<Ident>,
+    /// The innermost aggregate function the name is an argument of, e.g. `SUM`
+    pub aggregated_by: Option<String>,
+}
+
+impl Reference {
+    /// Whether `part` of this reference names `name`. Quoted names must match exactly; other names
+    /// are compared ignoring case, as Snowflake does.
+    pub fn part_is(&self, part: usize, name: &str) -> bool {
+        self.parts
+            .get(part)
+            .is_some_and(|ident| match ident.quote_style {
+                Some(_) => ident.value == name,
+                None => ident.value.eq_ignore_ascii_case(name),
+            })
+    }
+
+    /// Where `part` of this reference is, as a 1-based line and column within the expression
+    pub fn span(&self, part: usize) -> Span {
+        let ident = &self.parts[part];
+        let quotes = if ident.quote_style.is_some() { 2 } else { 0 };
+        Span {
+            line: ident.span.start.line as usize,
+            column: ident.span.start.column as usize,
+            length: ident.value.chars().count() + quotes,
+        }
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.parts.iter().map(|ident| ident.to_string()).collect();
+        write!(f, "{}", parts.join("."))
+    }
+}
+
+/// How a function call combines rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+    /// An aggregate function, which combines the rows of each group into one value
+    Aggregate,
+    /// A function with an OVER clause, which computes a value for each row from a window of rows
+    Window,
+}
+
+/// A call to an aggregate or window function
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    /// The function name, in upper case
+    pub name: String,
+    pub kind: CallKind,
+    /// The innermost aggregate function this call is an argument of
+    pub aggregated_by: Option<String>,
+    /// Where the function name is, as a 1-based line and column within the expression
+    pub span: Span,
+}
+
+/// The names and aggregate or window function calls in an expression
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    /// The columns and metrics the expression refers to, in order. Date and time parts passed to
+    /// functions aren't included. If the expression has a subquery, this is empty, since names
+    /// there can refer to physical tables.
+    pub references: Vec<Reference>,
+    /// Aggregate and window function calls, outside of any subquery, in order
+    pub calls: Vec<Call>,
+}
+
+impl Analysis {
+    /// Whether the expression calls an aggregate function outside of a window
+    pub fn aggregates(&self) -> bool {
+        self.calls
+            .iter()
+            .any(|call| call.kind == CallKind::Aggregate)
+    }
+}
+
+/// Find the names and the aggregate and window function calls in `expr`
+pub fn analyze(expr: &Expr) -> Analysis {
+    let mut walker = Walker::default();
+    let _ = expr.visit(&mut walker);
+    if walker.has_subquery {
+        walker.analysis.references.clear();
+    }
+    walker.analysis
+}
+
+#[derive(Default)]
+struct Walker {
+    analysis: Analysis,
+    /// The aggregate calls being visited, innermost last
+    aggregates: Vec<String>,
+    /// For each expression being visited, whether it is an aggregate call
+    visiting: Vec<bool>,
+    /// How many subqueries deep the walk is
+    query_depth: usize,
+    has_subquery: bool,
+    /// Date parts seen as function arguments, which aren't names
+    date_parts: Vec<sqlparser::tokenizer::Span>,
+}
+
+impl Visitor for Walker {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, _query: &Query) -> ControlFlow<()> {
+        self.query_depth += 1;
+        self.has_subquery = true;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, _query: &Query) -> ControlFlow<()> {
+        self.query_depth -= 1;
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+        let mut is_aggregate = false;
+        if self.query_depth == 0 {
+            let aggregated_by = self.aggregates.last().cloned();
+            match expr {
+                Expr::Function(function) => {
+                    if let Some(ident) = date_part_argument(function) {
+                        self.date_parts.push(ident.span);
+                    }
+                    if let Some(call) = call_of(function, aggregated_by) {
+                        if call.kind == CallKind::Aggregate {
+                            is_aggregate = true;
+                            self.aggregates.push(call.name.clone());
+                        }
+                        self.analysis.calls.push(call);
+                    }
+                }
+                Expr::Identifier(ident) if !self.date_parts.contains(&ident.span) => {
+                    self.analysis.references.push(Reference {
+                        parts: vec![ident.clone()],
+                        aggregated_by,
+                    })
+                }
+                Expr::CompoundIdentifier(parts) => self.analysis.references.push(Reference {
+                    parts: parts.clone(),
+                    aggregated_by,
+                }),
+                _ => {}
+            }
+        }
+        self.visiting.push(is_aggregate);
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_expr(&mut self, _expr: &Expr) -> ControlFlow<()> {
+        if self.visiting.pop() == Some(true) {
+            self.aggregates.pop();
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Describe `function` if it's an aggregate or window function
+fn call_of(function: &Function, aggregated_by: Option<String>) -> Option<Call> {
+    let name = function.name.to_string().to_uppercase();
+    let kind = if function.over.is_some() {
+        CallKind::Window
+    } else if AGGREGATE_FUNCTIONS.split_whitespace().any(|f| f == name) {
+        CallKind::Aggregate
+    } else {
+        return None;
+    };
+
+    let start = function.name.span().start;
+    Some(Call {
+        span: Span {
+            line: start.line as usize,
+            column: start.column as usize,
+            length: name.chars().count(),
+        },
+        name,
+        kind,
+        aggregated_by,
+    })
+}
+
+/// The first argument of a call to a date or time function like DATEADD, if it's an unquoted date
+/// or time part
+fn date_part_argument(function: &Function) -> Option<&Ident> {
+    let name = function.name.to_string();
+    if !DATE_PART_FUNCTIONS
+        .split_whitespace()
+        .any(|f| f.eq_ignore_ascii_case(&name))
+    {
+        return None;
+    }
+    let FunctionArguments::List(list) = &function.args else {
+        return None;
+    };
+    match list.args.first()? {
+        FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Identifier(ident)))
+            if ident.quote_style.is_none()
+                && DATE_PARTS
+                    .split_whitespace()
+                    .any(|part| part.eq_ignore_ascii_case(&ident.value)) =>
+        {
+            Some(ident)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,45 @@
+/// Find the candidate most likely to be what the user meant by `name`.
+///
+/// Candidates are compared case-insensitively, and only returned if they are close enough to be a
+/// plausible typo.
+pub fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            let distance = strsim::osa_distance(&name, &candidate.to_lowercase());
+            (distance, candidate)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Suggestion text pointing at the closest candidate, or listing every candidate if none is close
+pub fn did_you_mean<'a>(
+    name: &str,
+    kind: &str,
+    candidates: impl IntoIterator<Item = &'a str> + Clone,
+) -> Option<String> {
+    if let Some(candidate) = closest_match(name, candidates.clone()) {
+        return Some(format!("Did you mean '{}'?", candidate));
+    }
+
+    // A model with duplicate names would otherwise list them twice
+    let mut all: Vec<&str> = Vec::new();
+    for candidate in candidates {
+        if !all.contains(&candidate) {
+            all.push(candidate);
+        }
+    }
+    if all.is_empty() {
+        None
+    } else {
+        Some(format!("Known {}: {}", kind, all.join(", ")))
+    }
+}
@@ -0,0 +1,235 @@
+use crate::diagnostic::{Diagnostic, Location, NodePath};
+use crate::rules::{self, Rule, RULES};
+use crate::suggest;
+use crate::yaml_tree::{Node, Span};
+
+const IGNORE: &str = "ssvv-ignore:";
+const IGNORE_FILE: &str = "ssvv-ignore-file:";
+
+/// Rules that are reported before suppressions are read, or by them
+const UNSUPPRESSIBLE: &[&Rule] = &[
+    &rules::INVALID_YAML,
+    &rules::INVALID_STRUCTURE,
+    &rules::UNREADABLE_FILE,
+    &rules::INVALID_SUPPRESSION,
+    &rules::UNUSED_SUPPRESSION,
+];
+
+/// One rule named in an `ssvv-ignore` or `ssvv-ignore-file` comment
+struct Suppression {
+    rule: &'static Rule,
+    /// The node the comment applies to, or `None` for the whole file
+    node: Option<NodePath>,
+    /// Where the rule is named in the comment
+    span: Span,
+    used: bool,
+}
+
+/// The suppression comments in a document, and any problems with them
+pub(crate) struct Suppressions {
+    suppressions: Vec<Suppression>,
+    invalid: Vec<(Span, String)>,
+}
+
+impl Suppressions {
+    /// Read the suppression comments in `source`. Only comments on a line of their own count, and
+    /// not lines of a multi-line value that happen to start with `#`.
+    pub fn parse(source: &str, tree: Option<&Node>) -> Self {
+        let lines: Vec<&str> = source.lines().collect();
+        let scalars = tree.map_or_else(Vec::new, |tree| tree.multiline_scalars(source));
+        let mut suppressions = Suppressions {
+            suppressions: Vec::new(),
+            invalid: Vec::new(),
+        };
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some(comment) = line.trim_start().strip_prefix('#') else {
+                continue;
+            };
+            let line_number = index + 1;
+            if scalars
+                .iter()
+                .any(|&(first, last)| first < line_number && line_number <= last)
+            {
+                continue;
+            }
+            let comment = comment.trim_start();
+            let (directive, names) = if let Some(names) = comment.strip_prefix(IGNORE_FILE) {
+                (IGNORE_FILE, names)
+            } else if let Some(names) = comment.strip_prefix(IGNORE) {
+                (IGNORE, names)
+            } else {
+                continue;
+            };
+
+            // Byte offset of the rule names within the line
+            let names_offset = line.len() - names.len();
+            let comment_span = Span {
+                line: line_number,
+                column: line.chars().count() - line.trim_start().chars().count() + 1,
+                length: line.trim().chars().count(),
+            };
+
+            let node = if directive == IGNORE_FILE {
+                None
+            } else {
+                let next = lines
+                    .iter()
+                    .enumerate()
+                    .skip(index + 1)
+                    .find(|(_, line)| {
+                        let line = line.trim();
+                        !line.is_empty() && !line.starts_with('#')
+                    })
+                    .map(|(next, _)| next + 1);
+                match next.and_then(|next| tree?.path_starting_on(next)) {
+                    Some(path) => Some(path),
+                    None => {
+                        suppressions.invalid.push((
+                            comment_span,
+                            "'ssvv-ignore' comment isn't followed by a node to apply to"
+                                .to_string(),
+                        ));
+                        continue;
+                    }
+                }
+            };
+
+            let mut named = false;
+            for (offset, name) in split_names(names) {
+                named = true;
+                let span = Span {
+                    line: line_number,
+                    column: line[..names_offset + offset].chars().count() + 1,
+                    length: name.chars().count(),
+                };
+                suppressions.add(name, node.clone(), span);
+            }
+            if !named {
+                suppressions.invalid.push((
+                    comment_span,
+                    format!(
+                        "'{}' comment doesn't name any rules",
+                        directive.trim_end_matches(':')
+                    ),
+                ));
+            }
+        }
+
+        suppressions
+    }
+
+    fn add(&mut self, name: &str, node: Option<NodePath>, span: Span) {
+        let Some(rule) = rules::find_rule(name) else {
+            let mut message = format!("Unknown rule '{}' in suppression comment.", name);
+            let names = RULES.iter().map(|rule| rule.name);
+            if let Some(candidate) = suggest::closest_match(name, names) {
+                message.push_str(&format!(" Did you mean '{}'?", candidate));
+            }
+            self.invalid.push((span, message));
+            return;
+        };
+        if UNSUPPRESSIBLE.iter().any(|fixed| fixed.code == rule.code) {
+            self.invalid.push((
+                span,
+                format!("Rule {} ({}) can't be suppressed", rule.code, rule.name),
+            ));
+            return;
+        }
+
+        self.suppressions.push(Suppression {
+            rule,
+            node,
+            span,
+            used: false,
+        });
+    }
+
+    /// Remove the diagnostics that a comment suppresses
+    pub fn apply(&mut self, diagnostics: &mut Vec<Diagnostic>) {
+        diagnostics.retain(|diagnostic| {
+            let mut suppressed = false;
+            for suppression in &mut self.suppressions {
+                let applies = suppression.rule.code == diagnostic.rule.code
+                    && suppression
+                        .node
+                        .as_ref()
+                        .is_none_or(|node| diagnostic.path.starts_with(node));
+                if applies {
+                    suppression.used = true;
+                    suppressed = true;
+                }
+            }
+            !suppressed
+        });
+    }
+
+    /// Diagnostics for comments that can't be applied, and, if `report_unused`, for suppressions
+    /// that didn't remove anything
+    pub fn diagnostics(
+        &self,
+        file_name: &str,
+        source: &str,
+        report_unused: bool,
+    ) -> Vec<Diagnostic> {
+        let locate = |span: &Span| {
+            Some(Location::new(
+                file_name,
+                source,
+                span.line,
+                span.column,
+                span.length,
+            ))
+        };
+
+        let mut diagnostics: Vec<Diagnostic> = self
+            .invalid
+            .iter()
+            .map(|(span, message)| {
+                let mut diagnostic = Diagnostic::new(&rules::INVALID_SUPPRESSION, message.clone());
+                diagnostic.location = locate(span);
+                diagnostic
+            })
+            .collect();
+
+        if report_unused {
+            for suppression in self.suppressions.iter().filter(|s| !s.used) {
+                let scope = match &suppression.node {
+                    Some(node) => format!("'{}'", node),
+                    None => "this file".to_string(),
+                };
+                let mut diagnostic = Diagnostic::new(
+                    &rules::UNUSED_SUPPRESSION,
+                    format!(
+                        "Suppression of {} ({}) is unused: nothing in {} reports it",
+                        suppression.rule.code, suppression.rule.name, scope
+                    ),
+                )
+                .with_suggestion("Remove the rule from the comment");
+                diagnostic.location = locate(&suppression.span);
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        diagnostics.sort_by_key(|d| d.location.as_ref().map(|l| (l.line, l.column)));
+        diagnostics
+    }
+}
+
+/// The rule names in a comment, separated by commas or whitespace, with their byte offsets
+fn split_names(names: &str) -> Vec<(usize, &str)> {
+    let mut found = Vec::new();
+    let mut start = None;
+    for (offset, c) in names.char_indices().chain([(names.len(), ',')]) {
+        let separator = c == ',' || c.is_whitespace();
+        match (start, separator) {
+            (None, false) => start = Some(offset),
+            (Some(begin), true) => {
+                found.push((begin, &names[begin..offset]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    found
+}
@@ -0,0 +1,333 @@
+use crate::diagnostic::{NodePath, PathSegment};
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, ScanError, TScalarStyle};
+
+/// A region of the YAML source (1-based line and column)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    /// Number of characters to underline, starting at `column`
+    pub length: usize,
+}
+
+impl Span {
+    fn at(mark: Marker, length: usize) -> Self {
+        Span {
+            line: mark.line(),
+            // yaml-rust2 reports 0-based columns
+            column: mark.col() + 1,
+            length: length.max(1),
+        }
+    }
+}
+
+/// A YAML node that remembers where it came from in the source
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub span: Span,
+    pub kind: NodeKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum NodeKind {
+    Scalar(String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(Node, Node)>),
+}
+
+impl Node {
+    /// The value of a scalar node
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.kind {
+            NodeKind::Scalar(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The value for `key`, if this is a mapping containing it
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        match &self.kind {
+            NodeKind::Mapping(entries) => entries
+                .iter()
+                .find(|(k, _)| k.as_str() == Some(key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// The span to underline when pointing at this node as a whole
+    fn anchor_span(&self) -> Span {
+        match &self.kind {
+            NodeKind::Mapping(entries) if !entries.is_empty() => entries[0].0.span,
+            _ => self.span,
+        }
+    }
+
+    /// Find the span for `path`, underlining the key for mapping entries.
+    ///
+    /// If part of the path doesn't exist (e.g. a required key is missing), the span of the
+    /// deepest ancestor that does exist is returned instead.
+    pub fn span_at(&self, path: &NodePath) -> Span {
+        let mut current = self;
+        let mut span = self.anchor_span();
+
+        for segment in path.segments() {
+            match (segment, &current.kind) {
+                (PathSegment::Key(key), NodeKind::Mapping(entries)) => {
+                    match entries.iter().find(|(k, _)| k.as_str() == Some(key)) {
+                        Some((k, v)) => {
+                            span = k.span;
+                            current = v;
+                        }
+                        None => break,
+                    }
+                }
+                (PathSegment::Index(index), NodeKind::Sequence(items)) => match items.get(*index) {
+                    Some(item) => {
+                        span = item.anchor_span();
+                        current = item;
+                    }
+                    None => break,
+                },
+                _ => break,
+            }
+        }
+
+        span
+    }
+
+    /// The node at `path`, if it exists
+    pub fn node_at(&self, path: &NodePath) -> Option<&Node> {
+        path.segments()
+            .iter()
+            .try_fold(self, |node, segment| match (segment, &node.kind) {
+                (PathSegment::Key(key), _) => node.get(key),
+                (PathSegment::Index(index), NodeKind::Sequence(items)) => items.get(*index),
+                _ => None,
+            })
+    }
+
+    /// Where the character at `line` and `column` (1-based) of this scalar's value is in `source`.
+    ///
+    /// Quoting, escapes, indentation and line folding mean the value isn't a plain copy of the
+    /// source, so the value's characters are matched against the source in order, ignoring
+    /// whitespace. A position past the end of the value maps to just after its last character.
+    pub fn value_position(
+        &self,
+        source: &str,
+        line: usize,
+        column: usize,
+    ) -> Option<(usize, usize)> {
+        let value = self.as_str()?;
+        let offset = value
+            .split('\n')
+            .take(line.saturating_sub(1))
+            .map(|text| text.chars().count() + 1)
+            .sum::<usize>()
+            + column.saturating_sub(1);
+
+        let mut source_chars = source
+            .lines()
+            .enumerate()
+            .skip(self.span.line.saturating_sub(1))
+            .flat_map(|(i, text)| {
+                text.chars()
+                    .enumerate()
+                    .map(move |(j, c)| (i + 1, j + 1, c))
+            })
+            .skip(self.span.column.saturating_sub(1))
+            .peekable();
+        // Plain scalars can't start with a quote or block indicator, so one there isn't part of
+        // the value
+        source_chars.next_if(|&(_, _, c)| matches!(c, '"' | '\'' | '|' | '>'));
+
+        let mut after_last = (self.span.line, self.span.column);
+        for (i, c) in value.chars().enumerate() {
+            if c.is_whitespace() {
+                continue;
+            }
+            let (found_line, found_column, _) = source_chars.find(|&(_, _, s)| s == c)?;
+            if i >= offset {
+                return Some((found_line, found_column));
+            }
+            after_last = (found_line, found_column + 1);
+        }
+        Some(after_last)
+    }
+
+    /// The source lines of every scalar that continues past its first line, such as a `|` or `>`
+    /// block, as (first line, last line)
+    pub fn multiline_scalars(&self, source: &str) -> Vec<(usize, usize)> {
+        let mut ranges = Vec::new();
+        self.collect_multiline_scalars(source, &mut ranges);
+        ranges
+    }
+
+    fn collect_multiline_scalars(&self, source: &str, ranges: &mut Vec<(usize, usize)>) {
+        match &self.kind {
+            NodeKind::Scalar(value) => {
+                let last_line = value.split('\n').next_back().unwrap_or("");
+                let end = value.split('\n').count();
+                let Some((end_line, _)) =
+                    self.value_position(source, end, last_line.chars().count() + 1)
+                else {
+                    return;
+                };
+                if end_line > self.span.line {
+                    ranges.push((self.span.line, end_line));
+                }
+            }
+            NodeKind::Sequence(items) => {
+                for item in items {
+                    item.collect_multiline_scalars(source, ranges);
+                }
+            }
+            NodeKind::Mapping(entries) => {
+                for (key, value) in entries {
+                    key.collect_multiline_scalars(source, ranges);
+                    value.collect_multiline_scalars(source, ranges);
+                }
+            }
+        }
+    }
+
+    /// Render `path` with sequence items identified by their `name` key where they have one, e.g.
+    /// `tables[runs].dimensions[0]` for a dimension without a name
+    pub fn named_path(&self, path: &NodePath) -> String {
+        let mut current = Some(self);
+        let mut rendered = String::new();
+
+        for segment in path.segments() {
+            match segment {
+                PathSegment::Key(key) => {
+                    if !rendered.is_empty() {
+                        rendered.push('.');
+                    }
+                    rendered.push_str(key);
+                    current = current.and_then(|node| node.get(key));
+                }
+                PathSegment::Index(index) => {
+                    let item = current.and_then(|node| match &node.kind {
+                        NodeKind::Sequence(items) => items.get(*index),
+                        _ => None,
+                    });
+                    match item.and_then(|item| item.get("name")?.as_str()) {
+                        Some(name) => rendered.push_str(&format!("[{}]", name)),
+                        None => rendered.push_str(&format!("[{}]", index)),
+                    }
+                    current = item;
+                }
+            }
+        }
+
+        rendered
+    }
+
+    /// The path of the outermost node that starts on `line`: a sequence item, or a mapping entry
+    /// (which starts at its key)
+    pub fn path_starting_on(&self, line: usize) -> Option<NodePath> {
+        self.find_starting_on(line, &NodePath::root())
+    }
+
+    fn find_starting_on(&self, line: usize, path: &NodePath) -> Option<NodePath> {
+        match &self.kind {
+            NodeKind::Scalar(_) => None,
+            NodeKind::Sequence(items) => items.iter().enumerate().find_map(|(index, item)| {
+                let item_path = path.index(index);
+                if item.anchor_span().line == line {
+                    Some(item_path)
+                } else {
+                    item.find_starting_on(line, &item_path)
+                }
+            }),
+            NodeKind::Mapping(entries) => entries.iter().find_map(|(key, value)| {
+                let entry_path = path.key(key.as_str()?);
+                if key.span.line == line {
+                    Some(entry_path)
+                } else {
+                    value.find_starting_on(line, &entry_path)
+                }
+            }),
+        }
+    }
+}
+
+/// Parse `source` into a span-preserving node tree. A document with no content, such as an empty
+/// file or one with only comments, is valid YAML without a root node.
+pub fn parse(source: &str) -> Result<Option<Node>, ScanError> {
+    let mut builder = TreeBuilder::default();
+    Parser::new_from_str(source).load(&mut builder, false)?;
+    Ok(builder.root)
+}
+
+/// A collection that is still being filled in by the parser
+enum Partial {
+    Sequence(Span, Vec<Node>),
+    Mapping(Span, Vec<(Node, Node)>, Option<Node>),
+}
+
+#[derive(Default)]
+struct TreeBuilder {
+    stack: Vec<Partial>,
+    root: Option<Node>,
+}
+
+impl TreeBuilder {
+    fn push_node(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            None => self.root = Some(node),
+            Some(Partial::Sequence(_, items)) => items.push(node),
+            Some(Partial::Mapping(_, entries, pending_key)) => match pending_key.take() {
+                Some(key) => entries.push((key, node)),
+                None => *pending_key = Some(node),
+            },
+        }
+    }
+}
+
+impl MarkedEventReceiver for TreeBuilder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        match event {
+            Event::Scalar(value, style, _, _) => {
+                let first_line = value.lines().next().unwrap_or("").chars().count();
+                let length = match style {
+                    TScalarStyle::Plain => first_line,
+                    TScalarStyle::SingleQuoted | TScalarStyle::DoubleQuoted => first_line + 2,
+                    TScalarStyle::Literal | TScalarStyle::Folded => 1,
+                };
+                self.push_node(Node {
+                    span: Span::at(mark, length),
+                    kind: NodeKind::Scalar(value),
+                });
+            }
+            Event::Alias(_) => self.push_node(Node {
+                span: Span::at(mark, 1),
+                kind: NodeKind::Scalar(String::new()),
+            }),
+            Event::SequenceStart(_, _) => {
+                self.stack
+                    .push(Partial::Sequence(Span::at(mark, 1), Vec::new()));
+            }
+            Event::MappingStart(_, _) => {
+                self.stack
+                    .push(Partial::Mapping(Span::at(mark, 1), Vec::new(), None));
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                let node = match self.stack.pop() {
+                    Some(Partial::Sequence(span, items)) => Node {
+                        span,
+                        kind: NodeKind::Sequence(items),
+                    },
+                    Some(Partial::Mapping(span, entries, _)) => Node {
+                        span,
+                        kind: NodeKind::Mapping(entries),
+                    },
+                    None => return,
+                };
+                self.push_node(node);
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,12 +1,21 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // Import the public functions from main.rs
-use snowflake_semantic_view_validator::{format_error, format_success, validate_file};
+use snowflake_semantic_view_validator::{
+    collect_model_files, find_rule, format_dependency_tree, format_diagnostic_changes,
+    format_error, format_file_summary, format_github_annotations, format_success,
+    format_success_with_thresholds, parse_file, validate_file, validate_file_with_options,
+    validate_files, validate_reader, validate_str, validate_str_with_options, Baseline, Config,
+    CoverageThresholds, DataTypeError, DependencyGraph, DiagnosticChanges, DiagnosticKey,
+    FileReport, Outcome, Report, SnowflakeType, Threshold, TimestampKind, ValidationOptions,
+    VectorElement, WarningPolicy, RULES,
+};
 
+// Relative to the crate root (the working directory for `cargo test`), so that file names in
+// diagnostics match the expected output regardless of where the repository is checked out
 fn get_fixture_path(fixture_name: &str) -> PathBuf {
-    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    path.push("tests");
+    let mut path = PathBuf::from("tests");
     path.push("fixtures");
     path.push(format!("{}.yaml", fixture_name));
     path
@@ -36,10 +45,8 @@ fn run_valid_test_case(fixture_name: &str) {
     let doc = format_success(&validation_result.model);
     let actual = doc.render_plain();
 
-    let expected = fs::read_to_string(&expected_path).expect(&format!(
-        "Failed to read expected output file: {:?}",
-        expected_path
-    ));
+    let expected = fs::read_to_string(&expected_path)
+        .unwrap_or_else(|_| panic!("Failed to read expected output file: {:?}", expected_path));
 
     assert_eq!(
         actual.trim(),
@@ -66,10 +73,8 @@ fn run_invalid_test_case(fixture_name: &str) {
     let doc = format_error(&error);
     let actual = doc.render_plain();
 
-    let expected = fs::read_to_string(&expected_path).expect(&format!(
-        "Failed to read expected output file: {:?}",
-        expected_path
-    ));
+    let expected = fs::read_to_string(&expected_path)
+        .unwrap_or_else(|_| panic!("Failed to read expected output file: {:?}", expected_path));
 
     assert_eq!(
         actual.trim(),
@@ -91,6 +96,20 @@ fn test_valid_with_relationships() {
     run_valid_test_case("valid_with_relationships");
 }
 
+// The models the README and help text show being validated
+#[test]
+fn test_example_models_are_valid() {
+    for path in [
+        "speedrun.yaml",
+        "minimal.yaml",
+        "examples/complete_example.yaml",
+    ] {
+        if let Err(error) = validate_file(path) {
+            panic!("{} should be valid, but failed with:\n{}", path, error);
+        }
+    }
+}
+
 #[test]
 fn test_invalid_missing_name() {
     run_invalid_test_case("invalid_missing_name");
@@ -106,6 +125,86 @@ fn test_invalid_yaml_syntax() {
     run_invalid_test_case("invalid_yaml_syntax");
 }
 
+#[test]
+fn test_invalid_multiple_errors() {
+    run_invalid_test_case("invalid_multiple_errors");
+}
+
+#[test]
+fn test_invalid_relationship_references() {
+    run_invalid_test_case("invalid_relationship_references");
+}
+
+#[test]
+fn test_invalid_relationship_primary_keys() {
+    run_invalid_test_case("invalid_relationship_primary_keys");
+}
+
+#[test]
+fn test_invalid_unknown_keys() {
+    run_invalid_test_case("invalid_unknown_keys");
+}
+
+#[test]
+fn test_invalid_misspelled_required_key() {
+    run_invalid_test_case("invalid_misspelled_required_key");
+}
+
+#[test]
+fn test_invalid_duplicate_names() {
+    run_invalid_test_case("invalid_duplicate_names");
+}
+
+#[test]
+fn test_invalid_data_types() {
+    run_invalid_test_case("invalid_data_types");
+}
+
+#[test]
+fn test_invalid_time_dimension_types() {
+    run_invalid_test_case("invalid_time_dimension_types");
+}
+
+#[test]
+fn test_invalid_suppressions() {
+    run_invalid_test_case("invalid_suppressions");
+}
+
+#[test]
+fn test_invalid_suppressions_in_block_scalars() {
+    run_invalid_test_case("invalid_suppressions_in_block_scalars");
+}
+
+#[test]
+fn test_invalid_join_type() {
+    run_invalid_test_case("invalid_join_type");
+}
+
+#[test]
+fn test_invalid_spec_values() {
+    run_invalid_test_case("invalid_spec_values");
+}
+
+#[test]
+fn test_invalid_expressions() {
+    run_invalid_test_case("invalid_expressions");
+}
+
+#[test]
+fn test_invalid_references() {
+    run_invalid_test_case("invalid_references");
+}
+
+#[test]
+fn test_invalid_aggregation() {
+    run_invalid_test_case("invalid_aggregation");
+}
+
+#[test]
+fn test_invalid_metric_dependencies() {
+    run_invalid_test_case("invalid_metric_dependencies");
+}
+
 #[test]
 fn test_valid_module_custom_instructions() {
     run_valid_test_case("valid_module_custom_instructions");
@@ -126,9 +225,11 @@ fn test_valid_composite_primary_key_three_columns() {
     run_valid_test_case("valid_composite_primary_key_three_columns");
 }
 
+// Written before relationships had to join on the right table's primary key, which PRODUCTS
+// and REGIONS don't declare
 #[test]
 fn test_valid_multiple_relationships() {
-    run_valid_test_case("valid_multiple_relationships");
+    run_invalid_test_case("valid_multiple_relationships");
 }
 
 #[test]
@@ -136,7 +237,622 @@ fn test_valid_multiple_queries() {
     run_valid_test_case("valid_multiple_queries");
 }
 
+// INVENTORY declares no primary key for the composite join to match
 #[test]
 fn test_valid_composite_join_keys() {
-    run_valid_test_case("valid_composite_join_keys");
+    run_invalid_test_case("valid_composite_join_keys");
+}
+
+#[test]
+fn test_valid_composite_primary_key_relationship() {
+    run_valid_test_case("valid_composite_primary_key_relationship");
+}
+
+#[test]
+fn test_rule_codes_are_unique_and_findable() {
+    for (i, rule) in RULES.iter().enumerate() {
+        assert!(
+            RULES[i + 1..]
+                .iter()
+                .all(|other| other.code != rule.code && other.name != rule.name),
+            "Rule '{}' ({}) is registered more than once",
+            rule.name,
+            rule.code
+        );
+        assert_eq!(find_rule(rule.code), Some(*rule));
+        assert_eq!(find_rule(&rule.name.to_uppercase()), Some(*rule));
+    }
+}
+
+#[test]
+fn test_snowflake_type_parsing() {
+    let parses_to = [
+        ("VARCHAR", SnowflakeType::Varchar { length: 16_777_216 }),
+        ("string", SnowflakeType::Varchar { length: 16_777_216 }),
+        ("CHAR", SnowflakeType::Varchar { length: 1 }),
+        ("varchar( 255 )", SnowflakeType::Varchar { length: 255 }),
+        (
+            "NUMBER",
+            SnowflakeType::Number {
+                precision: 38,
+                scale: 0,
+            },
+        ),
+        (
+            "decimal(10, 2)",
+            SnowflakeType::Number {
+                precision: 10,
+                scale: 2,
+            },
+        ),
+        (
+            "BIGINT",
+            SnowflakeType::Number {
+                precision: 38,
+                scale: 0,
+            },
+        ),
+        ("double  precision", SnowflakeType::Float),
+        (
+            "TIMESTAMP",
+            SnowflakeType::Timestamp {
+                kind: TimestampKind::Ntz,
+                precision: 9,
+            },
+        ),
+        (
+            "TIMESTAMP_TZ(3)",
+            SnowflakeType::Timestamp {
+                kind: TimestampKind::Tz,
+                precision: 3,
+            },
+        ),
+        (
+            "VECTOR(FLOAT, 256)",
+            SnowflakeType::Vector {
+                element: VectorElement::Float,
+                dimension: 256,
+            },
+        ),
+    ];
+    for (input, expected) in parses_to {
+        assert_eq!(SnowflakeType::parse(input), Ok(expected), "{}", input);
+    }
+
+    assert!(matches!(
+        SnowflakeType::parse("VARCHR"),
+        Err(DataTypeError::UnknownType(_))
+    ));
+    for input in [
+        "NUMBER(39)",
+        "NUMBER(5,6)",
+        "TIME(10)",
+        "BOOLEAN(1)",
+        "VARCHAR(n)",
+    ] {
+        assert!(
+            matches!(
+                SnowflakeType::parse(input),
+                Err(DataTypeError::InvalidParameters { .. })
+            ),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_json_report() {
+    let invalid = get_fixture_path("invalid_relationship_references");
+    let valid = get_fixture_path("valid_with_relationships");
+    let report = Report::new(vec![
+        FileReport::new("invalid.yaml", &validate_file(&invalid)),
+        FileReport::new("valid.yaml", &validate_file(&valid)),
+    ]);
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+
+    assert_eq!(json["version"], 1);
+
+    let invalid = &json["results"][0];
+    assert_eq!(invalid["file"], "invalid.yaml");
+    assert_eq!(invalid["valid"], false);
+    assert_eq!(invalid["model"], serde_json::Value::Null);
+    let diagnostic = &invalid["diagnostics"][0];
+    assert_eq!(diagnostic["code"], "SSVV009");
+    assert_eq!(diagnostic["severity"], "error");
+    assert_eq!(diagnostic["path"], "relationships[0].right_table");
+    assert_eq!(diagnostic["line"], 40);
+    assert_eq!(diagnostic["column"], 5);
+    assert_eq!(diagnostic["suggestion"], "Did you mean 'games'?");
+
+    let valid = &json["results"][1];
+    assert_eq!(valid["valid"], true);
+    assert_eq!(valid["errors"], 0);
+    let model = &valid["model"];
+    assert_eq!(model["tables"].as_array().unwrap().len(), 2);
+    assert_eq!(model["relationships"], 1);
+    assert!(model["data_quality"]["described_percent"].is_number());
+}
+
+#[test]
+fn test_sarif_report() {
+    let fixture = get_fixture_path("invalid_duplicate_names");
+    let report = Report::new(vec![FileReport::new(
+        "models/duplicates.yaml",
+        &validate_file(&fixture),
+    )]);
+    let sarif: serde_json::Value = serde_json::from_str(&report.to_sarif()).unwrap();
+
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), RULES.len());
+
+    let results = run["results"].as_array().unwrap();
+    let result = results.iter().find(|r| r["ruleId"] == "SSVV014").unwrap();
+    assert_eq!(
+        rules[result["ruleIndex"].as_u64().unwrap() as usize]["id"],
+        "SSVV014"
+    );
+    assert_eq!(result["level"], "error");
+    assert_eq!(
+        result["message"]["text"],
+        "Table 'Runs' has the same name as table 'runs'"
+    );
+    assert_eq!(
+        result["properties"]["help"]["text"],
+        "Rename one of them. Names are compared case-insensitively."
+    );
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(
+        location["artifactLocation"]["uri"],
+        "models/duplicates.yaml"
+    );
+    assert_eq!(location["region"]["startLine"], 45);
+    assert_eq!(location["region"]["startColumn"], 5);
+    assert_eq!(location["region"]["endColumn"], 9);
+    let related = &result["relatedLocations"][0]["physicalLocation"];
+    assert_eq!(related["region"]["startLine"], 4);
+    assert_eq!(related["region"]["startColumn"], 5);
+    assert_eq!(related["region"]["endColumn"], 9);
+}
+
+#[test]
+fn test_junit_report() {
+    let report = Report::new(vec![
+        FileReport::new(
+            "time_dimensions.yaml",
+            &validate_file(get_fixture_path("invalid_time_dimension_types")),
+        ),
+        FileReport::new(
+            "syntax.yaml",
+            &validate_file(get_fixture_path("invalid_yaml_syntax")),
+        ),
+    ]);
+    let xml = report.to_junit();
+
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+    assert!(xml.contains(&format!(
+        "<testsuites name=\"ssvv\" tests=\"{}\" failures=\"2\">",
+        RULES.len() * 2
+    )));
+    assert!(xml.contains(&format!(
+        "<testsuite name=\"time_dimensions.yaml\" tests=\"{}\" failures=\"1\" errors=\"0\" skipped=\"0\">",
+        RULES.len()
+    )));
+    assert!(xml.contains("<failure type=\"SSVV017\" message=\"Time dimension &apos;run_date&apos;"));
+    assert!(xml.contains("time_dimensions.yaml:22:9: error: Time dimension"));
+    assert!(xml.contains("<system-out>time_dimensions.yaml:15:9: warning: Dimension"));
+
+    // Rules that never ran because the file couldn't be parsed are skipped, not passed
+    assert!(xml.contains(&format!(
+        "<testsuite name=\"syntax.yaml\" tests=\"{}\" failures=\"1\" errors=\"0\" skipped=\"{}\">",
+        RULES.len(),
+        RULES.len() - 1
+    )));
+}
+
+#[test]
+fn test_github_annotations() {
+    let error = validate_file(get_fixture_path("invalid_relationship_references")).unwrap_err();
+    let output = format_github_annotations("ignored.yaml", &error.diagnostics);
+    let first = output.lines().next().unwrap();
+
+    assert_eq!(output.lines().count(), error.diagnostics.len());
+    assert_eq!(
+        first,
+        "::error file=tests/fixtures/invalid_relationship_references.yaml,line=40,col=5,endColumn=16,\
+         title=SSVV009 unknown-relationship-table::Relationship 'runs_to_games' has right_table \
+         'gmaes', which is not a table in this model%0A%0ADid you mean 'games'?"
+    );
+
+    // Diagnostics without a source location fall back to the given file name
+    let error = validate_file("tests/fixtures/does_not_exist.yaml").unwrap_err();
+    let output = format_github_annotations("does_not_exist.yaml", &error.diagnostics);
+    assert!(output.starts_with(
+        "::error file=does_not_exist.yaml,title=SSVV008 unreadable-file::Failed to read file"
+    ));
+}
+
+#[test]
+fn test_collect_model_files() {
+    let inputs = [
+        "tests/fixtures/valid_basic.yaml".to_string(),
+        "tests/fixtures/valid_multiple_*.yaml".to_string(),
+        "tests/fixtures".to_string(),
+        "tests/fixtures/*.yml".to_string(),
+        "tests/fixtures/missing.yaml".to_string(),
+    ];
+    let set = collect_model_files(&inputs);
+
+    // Explicit files first, then globs and directories in order, without duplicates
+    let files: Vec<String> = set.files.iter().map(|p| p.display().to_string()).collect();
+    assert_eq!(files[0], "tests/fixtures/valid_basic.yaml");
+    assert_eq!(files[1], "tests/fixtures/valid_multiple_queries.yaml");
+    assert_eq!(files[2], "tests/fixtures/valid_multiple_relationships.yaml");
+    assert_eq!(files[3], "tests/fixtures/invalid_aggregation.yaml");
+    assert_eq!(files.last().unwrap(), "tests/fixtures/missing.yaml");
+    assert!(files.iter().all(|f| f.ends_with(".yaml")));
+    let unique: std::collections::HashSet<&String> = files.iter().collect();
+    assert_eq!(unique.len(), files.len());
+
+    assert_eq!(set.unmatched, vec!["tests/fixtures/*.yml".to_string()]);
+}
+
+#[test]
+fn test_file_summary() {
+    let files = vec![
+        get_fixture_path("valid_basic"),
+        get_fixture_path("invalid_multiple_errors"),
+    ];
+    let results = validate_files(&files, &ValidationOptions::default());
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+
+    let reports: Vec<FileReport> = files
+        .iter()
+        .zip(&results)
+        .map(|(file, result)| FileReport::new(file.display().to_string(), result))
+        .collect();
+    let summary = format_file_summary(&reports).render_plain();
+
+    assert!(summary.contains("FILES (2)"));
+    assert!(summary
+        .contains("tests/fixtures/valid_basic.yaml             | ok     |      0 |        0"));
+    assert!(summary
+        .contains("tests/fixtures/invalid_multiple_errors.yaml | FAILED |      4 |        1"));
+    assert!(summary.contains("1 passed, 1 failed, with 4 errors and 1 warning."));
+}
+
+#[test]
+fn test_validate_str_matches_validate_file() {
+    let fixture_name = "invalid_relationship_references";
+    let contents = fs::read_to_string(get_fixture_path(fixture_name)).unwrap();
+    let expected = fs::read_to_string(get_expected_path(fixture_name)).unwrap();
+
+    // Labelled with the fixture's path, the output is identical to reading the file
+    let label = get_fixture_path(fixture_name).display().to_string();
+    let error = validate_str(&contents, &label).unwrap_err();
+    assert_eq!(format_error(&error).render_plain().trim(), expected.trim());
+
+    let error = validate_reader(contents.as_bytes(), "generated.yaml").unwrap_err();
+    let location = error.diagnostics[0].location.as_ref().unwrap();
+    assert_eq!(location.file, "generated.yaml");
+}
+
+#[test]
+fn test_validate_reader_failure() {
+    struct FailingReader;
+    impl std::io::Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("pipe closed"))
+        }
+    }
+
+    let error = validate_reader(FailingReader, "<stdin>").unwrap_err();
+    assert_eq!(error.diagnostics[0].rule.code, "SSVV008");
+    assert_eq!(error.to_string(), "Failed to read <stdin>: pipe closed");
+}
+
+#[test]
+fn test_diagnostic_changes() {
+    let fixture = get_fixture_path("invalid_duplicate_names");
+    let name = fixture.display().to_string();
+    let original = fs::read_to_string(&fixture).unwrap();
+    let run = |contents: &str| {
+        let results = vec![validate_str(contents, &name)];
+        DiagnosticKey::collect(std::slice::from_ref(&name), &results)
+    };
+    let previous = run(&original);
+    assert_eq!(previous.len(), 10);
+
+    // Lines moving doesn't change a diagnostic
+    let shifted = format!("# Comment\n\n{}", original);
+    assert!(DiagnosticChanges::between(&previous, &run(&shifted)).is_empty());
+
+    let renamed = original.replace(
+        "      - name: verified\n        expr: VERIFIED_DATE",
+        "      - name: Verified\n        expr: VERIFIED_DATE",
+    );
+    let changes = DiagnosticChanges::between(&previous, &run(&renamed));
+    // The filter's unknown reference is reported under its new name too
+    assert_eq!(changes.appeared.len(), 2);
+    assert_eq!(changes.resolved.len(), 2);
+    assert_eq!(
+        changes.appeared[0].path,
+        "tables[runs].filters[Verified].expr"
+    );
+    assert_eq!(
+        changes.appeared[1].message,
+        "Filter 'Verified' has the same name as filter 'verified' in table 'runs'"
+    );
+    assert_eq!(
+        changes.resolved[1].path,
+        "tables[runs].filters[verified].name"
+    );
+
+    let output = format_diagnostic_changes(&changes).render_plain();
+    assert!(output.contains(&format!(
+        "+ error[SSVV014]: Filter 'Verified' has the same name as filter 'verified' in table 'runs' ({} tables[runs].filters[Verified].name)",
+        name
+    )));
+    assert!(output.contains("2 appeared, 2 resolved."));
+
+    // Inserting a table ahead of the existing problems shifts their indexes, but not their names
+    let inserted = original.replacen(
+        "tables:\n",
+        "tables:\n  - name: players\n    base_table:\n      database: KJ_SPEEDRUN\n      schema: PUBLIC\n      table: PLAYERS\n    dimensions:\n      - name: player_id\n        expr: PLAYER_ID\n        data_type: VARCHAR\n",
+        1,
+    );
+    assert!(DiagnosticChanges::between(&previous, &run(&inserted)).is_empty());
+}
+
+#[test]
+fn test_outcome() {
+    let validate = |names: &[&str]| {
+        let files: Vec<PathBuf> = names.iter().map(|name| get_fixture_path(name)).collect();
+        validate_files(&files, &ValidationOptions::default())
+    };
+    let allow = WarningPolicy::default();
+
+    assert_eq!(
+        Outcome::of(&validate(&["valid_basic"]), &allow),
+        Outcome::Success
+    );
+    assert_eq!(
+        Outcome::of(&validate(&["invalid_multiple_errors"]), &allow),
+        Outcome::SpecViolation
+    );
+    assert_eq!(
+        Outcome::of(&validate(&["invalid_join_type"]), &allow),
+        Outcome::SpecViolation
+    );
+    // Well-formed YAML that isn't a semantic model breaks the specification
+    assert_eq!(
+        Outcome::of(&validate(&["invalid_missing_name"]), &allow),
+        Outcome::SpecViolation
+    );
+    assert_eq!(
+        Outcome::of(&validate(&["invalid_yaml_syntax"]), &allow),
+        Outcome::YamlSyntaxError
+    );
+    // An empty document is valid YAML too, it just isn't a semantic model
+    for contents in ["", "# Nothing here yet\n"] {
+        let results = vec![validate_str(contents, "empty.yaml")];
+        let error = results[0].as_ref().unwrap_err();
+        assert_eq!(error.diagnostics[0].rule.code, "SSVV029");
+        assert_eq!(Outcome::of(&results, &allow), Outcome::SpecViolation);
+    }
+    // The most serious problem decides the outcome
+    assert_eq!(
+        Outcome::of(
+            &validate(&["invalid_multiple_errors", "missing", "invalid_yaml_syntax"]),
+            &allow
+        ),
+        Outcome::UnreadableFile
+    );
+
+    let warnings = validate(&["valid_legacy_custom_instructions"]);
+    assert_eq!(Outcome::of(&warnings, &allow), Outcome::Success);
+    let deny = WarningPolicy {
+        deny_warnings: true,
+        max_warnings: None,
+    };
+    assert_eq!(Outcome::of(&warnings, &deny), Outcome::LintFailure);
+    let max_one = WarningPolicy {
+        deny_warnings: false,
+        max_warnings: Some(1),
+    };
+    assert_eq!(Outcome::of(&warnings, &max_one), Outcome::Success);
+    assert_eq!(
+        max_one.violation(2).as_deref(),
+        Some("Found 2 warnings, more than the maximum of 1 (--max-warnings)")
+    );
+
+    let codes: Vec<i32> = [
+        Outcome::Success,
+        Outcome::SpecViolation,
+        Outcome::YamlSyntaxError,
+        Outcome::UnreadableFile,
+        Outcome::LintFailure,
+    ]
+    .iter()
+    .map(|outcome| outcome.exit_code())
+    .collect();
+    assert_eq!(codes, vec![0, 1, 3, 4, 5]);
+}
+
+#[test]
+fn test_config() {
+    let config = Config::parse(
+        r#"
+format = "json"
+include = ["models"]
+exclude = ["models/generated", "**/*.draft.yaml"]
+max-warnings = 3
+
+[rules]
+deprecated-custom-instructions = "error"
+SSVV014 = "warning"
+unknown-key = "off"
+
+[coverage]
+described = { good = 90 }
+"#,
+        Path::new("project/ssvv.toml"),
+    )
+    .unwrap();
+
+    assert_eq!(config.format.as_deref(), Some("json"));
+    assert_eq!(config.root(), PathBuf::from("project"));
+    assert_eq!(config.warnings.max_warnings, Some(3));
+    assert_eq!(config.coverage.described.good, 90.0);
+    assert_eq!(config.coverage.described.fair, 50.0);
+    assert!(config.is_excluded(Path::new("project/models/generated/sales.yaml")));
+    assert!(config.is_excluded(Path::new("project/models/sales.draft.yaml")));
+    assert!(!config.is_excluded(Path::new("project/models/sales.yaml")));
+
+    let options = config.validation_options();
+    // The deprecation warning becomes an error
+    let error = validate_file_with_options(
+        get_fixture_path("valid_legacy_custom_instructions"),
+        &options,
+    )
+    .unwrap_err();
+    assert_eq!(error.diagnostics[0].rule.code, "SSVV006");
+    assert!(error.diagnostics[0].is_error());
+    // Duplicate names are only warnings, which leaves the unknown references as the only errors
+    let error = validate_file_with_options(get_fixture_path("invalid_duplicate_names"), &options)
+        .unwrap_err();
+    assert_eq!(error.warnings().count(), 7);
+    assert!(error.errors().all(|d| d.rule.code == "SSVV022"));
+    // Unknown keys aren't reported at all, which leaves nothing wrong with the model
+    let result =
+        validate_file_with_options(get_fixture_path("invalid_unknown_keys"), &options).unwrap();
+    assert!(result.warnings.is_empty());
+}
+
+#[test]
+fn test_config_errors() {
+    let error = |contents: &str| {
+        Config::parse(contents, Path::new("ssvv.toml"))
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert_eq!(
+        error("[rules]\nempty-tabel = \"off\"\n"),
+        "Invalid configuration in ssvv.toml: unknown rule 'empty-tabel' in [rules]. Did you mean 'empty-table'? Run `ssvv explain` to list every rule."
+    );
+    assert_eq!(
+        error("[rules]\nSSVV008 = \"off\"\n"),
+        "Invalid configuration in ssvv.toml: rule SSVV008 (unreadable-file) can't be configured, because nothing else can be checked without it"
+    );
+    assert_eq!(
+        error("[coverage]\naliased = { good = 20, fair = 40 }\n"),
+        "Invalid configuration in ssvv.toml: coverage.aliased: fair (40) can't be higher than good (20)"
+    );
+    assert!(error("[rules]\nempty-table = \"warn\"\n").contains("unknown variant `warn`"));
+    assert!(error("max_warnings = 3\n").contains("unknown field `max_warnings`"));
+}
+
+#[test]
+fn test_coverage_thresholds() {
+    let result = validate_file(get_fixture_path("valid_basic")).unwrap();
+    let default = format_success(&result.model).render_plain();
+    assert!(default.contains("Aim for 80%+ described columns and 60%+ aliased columns"));
+
+    let thresholds = CoverageThresholds {
+        described: Threshold {
+            good: 95.0,
+            fair: 75.0,
+        },
+        ..CoverageThresholds::default()
+    };
+    let configured = format_success_with_thresholds(&result.model, &thresholds).render_plain();
+    assert!(configured.contains("Aim for 95%+ described columns and 60%+ aliased columns"));
+}
+
+#[test]
+fn test_baseline() {
+    let fixture = get_fixture_path("invalid_duplicate_names");
+    let name = fixture.display().to_string();
+    let original = fs::read_to_string(&fixture).unwrap();
+
+    let results = vec![validate_str(&original, &name)];
+    let baseline = Baseline::new(
+        Path::new("baseline.json"),
+        std::slice::from_ref(&name),
+        &results,
+    );
+    assert_eq!(baseline.len(), 10);
+    let entry = &baseline.entries[0];
+    assert_eq!(entry.file, "tests/fixtures/invalid_duplicate_names.yaml");
+    assert_eq!(entry.code, "SSVV014");
+    assert_eq!(entry.path, "metrics[run_count].name");
+    assert!(baseline
+        .entries
+        .iter()
+        .any(|entry| entry.path == "tables[runs].filters[verified].name"));
+
+    let options = ValidationOptions {
+        baseline: Some(std::sync::Arc::new(baseline)),
+        ..ValidationOptions::default()
+    };
+
+    // Every finding is in the baseline, so the model is valid
+    let result = validate_str_with_options(&original, &name, &options).unwrap();
+    assert!(result.warnings.is_empty());
+
+    // A new duplicate is reported, even though the items before it moved
+    let changed = original.replacen(
+        "    dimensions:\n",
+        "    dimensions:\n      - name: region\n        expr: REGION\n        data_type: VARCHAR\n      - name: REGION\n        expr: REGION\n        data_type: VARCHAR\n",
+        1,
+    );
+    let error = validate_str_with_options(&changed, &name, &options).unwrap_err();
+    assert_eq!(error.diagnostics.len(), 1);
+    assert_eq!(
+        error.diagnostics[0].message,
+        "Dimension 'REGION' has the same name as dimension 'region' in table 'runs'"
+    );
+}
+
+#[test]
+fn test_dependency_tree() {
+    let model = parse_file(get_fixture_path("invalid_metric_dependencies")).unwrap();
+    let graph = DependencyGraph::new(&model);
+    let name = |item: usize| graph.items()[item].qualified_name();
+
+    let cycles: Vec<Vec<String>> = graph
+        .cycles()
+        .into_iter()
+        .map(|cycle| cycle.into_iter().map(name).collect())
+        .collect();
+    assert_eq!(
+        cycles,
+        vec![
+            vec!["runs.best_time", "runs.fastest_time", "runs.best_time"],
+            vec!["weekly_growth", "weekly_growth"],
+        ]
+    );
+
+    let tables = graph.tables_used(graph.find_metrics("runs_per_player_per_game")[0]);
+    assert_eq!(
+        tables.into_iter().collect::<Vec<_>>(),
+        vec!["games", "players", "runs"]
+    );
+    assert_eq!(graph.find_metrics("RUNS.TOTAL_TIME").len(), 1);
+    assert!(graph.find_metrics("games.total_time").is_empty());
+
+    let tree = format_dependency_tree(&graph, graph.find_metrics("fastest_time")[0]);
+    let expected = "\
+DEPENDENCIES OF runs.fastest_time
+--------------------------------------------------------------------------------
+runs.fastest_time  metric: COALESCE(best_time, MIN(primary_time))
+├── runs.best_time  metric: fastest_time
+│   └── runs.fastest_time  metric: COALESCE(best_time, MIN(primary_time))  (cycle)
+└── runs.primary_time  fact: PRIMARY_TIME_SECONDS
+";
+    assert_eq!(tree.render_plain(), expected);
 }